@@ -0,0 +1,186 @@
+//! Merges the entries of several feeds into one, as "planet" sites do.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::{Entry, Feed};
+//! use atom_syndication::aggregate::{self, MergeOptions};
+//!
+//! let upstream = Feed {
+//!     id: String::from("http://test.blog/"),
+//!     title: "Test Blog".into(),
+//!     entries: vec![Entry {
+//!         id: String::from("http://test.blog/1"),
+//!         updated: String::from("2017-06-01T12:00:00Z"),
+//!         ..Default::default()
+//!     }],
+//!     ..Default::default()
+//! };
+//!
+//! let planet = Feed {
+//!     id: String::from("http://planet.test/"),
+//!     title: "Planet Test".into(),
+//!     ..Default::default()
+//! };
+//!
+//! let planet = aggregate::merge(planet, vec![upstream], &MergeOptions::default());
+//! let source = planet.entries[0].source.as_ref().unwrap();
+//! assert_eq!(source.id, Some(String::from("http://test.blog/")));
+//! assert_eq!(planet.updated, "2017-06-01T12:00:00Z");
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use chrono::{DateTime, FixedOffset};
+
+use ::{Entry, Feed, Source};
+use ::date;
+
+
+/// Options for `merge`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MergeOptions {
+    /// The most entries to keep, dropping the oldest. `None` keeps every entry.
+    pub max_entries: Option<usize>,
+}
+
+
+/// Merges the entries of `upstream` into `feed`, returning `feed` with the newest entries first.
+///
+/// Entries copied from an upstream feed are given an `atom:source` describing that feed, unless
+/// they already have one because they were themselves copied from another feed. Entries already
+/// in `feed` are kept as they are, so a previous result can be merged again with fresh copies of
+/// the upstream feeds.
+///
+/// When several entries have the same id, only the one with the newest `updated` is kept, or the
+/// first of them if they were updated at the same time. Entries with no id are never merged.
+/// Entries whose `updated` isn't a valid date sort after all others.
+///
+/// `feed.updated` is moved forward to the newest entry's `updated` if that is later.
+pub fn merge<I>(mut feed: Feed, upstream: I, options: &MergeOptions) -> Feed
+    where I: IntoIterator<Item = Feed>
+{
+    let mut entries = ::std::mem::take(&mut feed.entries);
+
+    for mut upstream in upstream {
+        let source = Source::from_feed(&upstream);
+
+        for mut entry in upstream.entries.drain(..) {
+            if entry.source.is_none() {
+                entry.source = Some(source.clone());
+            }
+            entries.push(entry);
+        }
+    }
+
+    let mut entries = deduplicate(entries);
+
+    // A stable sort, so that entries updated at the same time stay in the order they were given
+    entries.sort_by_key(|entry| Reverse(updated(entry)));
+
+    if let Some(max_entries) = options.max_entries {
+        entries.truncate(max_entries);
+    }
+
+    if let Some(newest) = entries.iter().find(|entry| updated(entry).is_some()) {
+        if updated(newest) > date::parse(&feed.updated) {
+            feed.updated = newest.updated.clone();
+        }
+    }
+
+    feed.entries = entries;
+    feed
+}
+
+
+fn deduplicate(entries: Vec<Entry>) -> Vec<Entry> {
+    let mut kept: Vec<Entry> = Vec::with_capacity(entries.len());
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        if entry.id.trim().is_empty() {
+            kept.push(entry);
+            continue;
+        }
+
+        match by_id.get(&entry.id) {
+            Some(&i) => {
+                if updated(&entry) > updated(&kept[i]) {
+                    kept[i] = entry;
+                }
+            }
+            None => {
+                by_id.insert(entry.id.clone(), kept.len());
+                kept.push(entry);
+            }
+        }
+    }
+
+    kept
+}
+
+
+fn updated(entry: &Entry) -> Option<DateTime<FixedOffset>> {
+    date::parse(&entry.updated)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{merge, MergeOptions};
+    use ::{Entry, Feed, Source};
+
+    fn entry(id: &str, updated: &str) -> Entry {
+        Entry { id: id.to_string(), updated: updated.to_string(), ..Default::default() }
+    }
+
+    fn upstream(id: &str, entries: Vec<Entry>) -> Feed {
+        Feed { id: id.to_string(), title: id.into(), entries, ..Default::default() }
+    }
+
+    #[test]
+    fn test_merge() {
+        let a = upstream("urn:a", vec![
+            entry("urn:1", "2017-06-01T12:00:00Z"),
+            entry("urn:2", "2017-06-03T12:00:00Z"),
+            entry("urn:3", "not a date"),
+        ]);
+
+        let mut reposted = entry("urn:1", "2017-06-02T14:00:00+02:00");
+        reposted.source = Some(Source { id: Some("urn:original".to_string()), ..Default::default() });
+        let b = upstream("urn:b", vec![reposted, entry("urn:4", "2017-06-02T13:00:00Z")]);
+
+        let planet = Feed {
+            id: "urn:planet".to_string(),
+            updated: "2017-06-02T00:00:00Z".to_string(),
+            entries: vec![entry("urn:0", "2017-05-01T00:00:00Z")],
+            ..Default::default()
+        };
+
+        let merged = merge(planet, vec![a, b], &MergeOptions::default());
+
+        let ids = merged.entries.iter().map(|e| &e.id[..]).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["urn:2", "urn:4", "urn:1", "urn:0", "urn:3"]);
+        assert_eq!(merged.updated, "2017-06-03T12:00:00Z");
+
+        let source_id = |i: usize| merged.entries[i].source.as_ref().and_then(|s| s.id.clone());
+        assert_eq!(source_id(0), Some("urn:a".to_string()));
+        assert_eq!(source_id(2), Some("urn:original".to_string()));
+        assert_eq!(source_id(3), None);
+        assert_eq!(merged.entries[0].source.as_ref().unwrap().title, Some("urn:a".into()));
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let a = upstream("urn:a", vec![
+            entry("urn:1", "2017-06-01T12:00:00Z"),
+            entry("urn:2", "2017-06-03T12:00:00Z"),
+            entry("urn:3", "2017-06-02T12:00:00Z"),
+        ]);
+
+        let merged = merge(Feed::default(), vec![a], &MergeOptions { max_entries: Some(2) });
+
+        let ids = merged.entries.iter().map(|e| &e.id[..]).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["urn:2", "urn:3"]);
+    }
+}
@@ -5,13 +5,20 @@ use ::{ElementUtils, NS, ViaXml};
 
 /// [The Atom Syndication Format § The "atom:category" Element]
 /// (https://tools.ietf.org/html/rfc4287#section-4.2.2)
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Category {
     pub term: String,
     pub scheme: Option<String>,
     pub label: Option<String>,
 }
 
+impl Category {
+    /// Whether both categories have the same term in the same scheme, whatever their labels.
+    pub fn same_category(&self, other: &Category) -> bool {
+        self.scheme == other.scheme && self.term == other.term
+    }
+}
+
 
 impl ViaXml for Category {
     fn to_xml(&self) -> Element {
@@ -35,9 +42,9 @@ impl ViaXml for Category {
         let label = elem.get_attribute("label", None).map(String::from);
 
         Ok(Category {
-            term: term,
-            scheme: scheme,
-            label: label,
+            term,
+            scheme,
+            label,
         })
     }
 }
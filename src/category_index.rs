@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+
+use ::{Entry, Feed};
+
+
+/// How category terms are compared when indexing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaseFolding {
+    /// Terms must match exactly
+    Exact,
+    /// Terms that differ only in ASCII case match
+    AsciiLowercase,
+    /// Terms that differ only in case match, using Unicode lowercasing
+    Lowercase,
+}
+
+impl CaseFolding {
+    fn fold(&self, term: &str) -> String {
+        match *self {
+            CaseFolding::Exact => term.to_string(),
+            CaseFolding::AsciiLowercase => term.to_ascii_lowercase(),
+            CaseFolding::Lowercase => term.to_lowercase(),
+        }
+    }
+}
+
+
+/// Which label to use when the same category is labelled differently in different entries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LabelMerge {
+    /// The first label in feed order
+    First,
+    /// The label used by the most entries, or the first of those that are equally common
+    MostCommon,
+    /// The longest label, or the first of those that are equally long
+    Longest,
+}
+
+
+/// Options for `CategoryIndex::new`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IndexOptions {
+    pub case_folding: CaseFolding,
+    pub label_merge: LabelMerge,
+}
+
+impl Default for IndexOptions {
+    fn default() -> Self {
+        IndexOptions {
+            case_folding: CaseFolding::Exact,
+            label_merge: LabelMerge::MostCommon,
+        }
+    }
+}
+
+
+/// A category found in a feed's entries, with every entry that has it.
+#[derive(Clone)]
+pub struct IndexedCategory<'a> {
+    pub scheme: Option<String>,
+    /// The term as it was first written, before any case folding
+    pub term: String,
+    /// The merged label of every occurrence of the category
+    pub label: Option<String>,
+    /// The entries with the category, in feed order
+    pub entries: Vec<&'a Entry>,
+    /// Each distinct label with the number of entries using it, in order of first use
+    labels: Vec<(String, usize)>,
+}
+
+impl<'a> IndexedCategory<'a> {
+    /// The label, or the term when no entry gives a label.
+    pub fn display_name(&self) -> &str {
+        self.label.as_ref().unwrap_or(&self.term)
+    }
+
+    fn merge_labels(&mut self, rule: LabelMerge) {
+        let mut best: Option<&(String, usize)> = None;
+
+        for candidate in &self.labels {
+            let better = match (best, rule) {
+                (None, _) => true,
+                (Some(_), LabelMerge::First) => false,
+                (Some(b), LabelMerge::MostCommon) => candidate.1 > b.1,
+                (Some(b), LabelMerge::Longest) => candidate.0.chars().count() > b.0.chars().count(),
+            };
+
+            if better {
+                best = Some(candidate);
+            }
+        }
+
+        self.label = best.map(|(label, _)| label.clone());
+    }
+}
+
+
+/// The categories of a feed's entries, grouped by scheme.
+///
+/// A category's `term` is only unique within its `scheme`, so the same term in two schemes is
+/// indexed as two categories. Categories with no scheme are grouped under `None`.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{CaseFolding, Category, CategoryIndex, Entry, Feed, IndexOptions};
+///
+/// let category = |term: &str, scheme: Option<&str>| Category {
+///     term: term.to_string(),
+///     scheme: scheme.map(String::from),
+///     ..Default::default()
+/// };
+///
+/// let feed = Feed {
+///     entries: vec![
+///         Entry { id: String::from("1"), categories: vec![category("Rust", None)], ..Default::default() },
+///         Entry { id: String::from("2"), categories: vec![category("rust", None)], ..Default::default() },
+///         Entry {
+///             id: String::from("3"),
+///             categories: vec![category("rust", Some("http://test.blog/metals"))],
+///             ..Default::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let options = IndexOptions { case_folding: CaseFolding::Lowercase, ..Default::default() };
+/// let index = CategoryIndex::new(&feed, &options);
+///
+/// assert_eq!(index.entries(None, "RUST").len(), 2);
+/// assert_eq!(index.entries(Some("http://test.blog/metals"), "rust")[0].id, "3");
+/// ```
+pub struct CategoryIndex<'a> {
+    case_folding: CaseFolding,
+    schemes: BTreeMap<Option<String>, BTreeMap<String, IndexedCategory<'a>>>,
+}
+
+impl<'a> CategoryIndex<'a> {
+    pub fn new(feed: &'a Feed, options: &IndexOptions) -> CategoryIndex<'a> {
+        let mut schemes: BTreeMap<Option<String>, BTreeMap<String, IndexedCategory<'a>>> = BTreeMap::new();
+
+        for entry in &feed.entries {
+            for category in &entry.categories {
+                let terms = schemes.entry(category.scheme.clone()).or_default();
+
+                let indexed = terms.entry(options.case_folding.fold(&category.term))
+                    .or_insert_with(|| IndexedCategory {
+                        scheme: category.scheme.clone(),
+                        term: category.term.clone(),
+                        label: None,
+                        entries: Vec::new(),
+                        labels: Vec::new(),
+                    });
+
+                // An entry that repeats a category is only counted once
+                if indexed.entries.last().is_some_and(|last| ::std::ptr::eq(*last, entry)) {
+                    continue;
+                }
+
+                indexed.entries.push(entry);
+
+                if let Some(ref label) = category.label {
+                    match indexed.labels.iter_mut().find(|&&mut (ref l, _)| l == label) {
+                        Some(&mut (_, ref mut count)) => *count += 1,
+                        None => indexed.labels.push((label.clone(), 1)),
+                    }
+                }
+            }
+        }
+
+        for indexed in schemes.values_mut().flat_map(|terms| terms.values_mut()) {
+            indexed.merge_labels(options.label_merge);
+        }
+
+        CategoryIndex {
+            case_folding: options.case_folding,
+            schemes,
+        }
+    }
+
+    /// Every scheme used by a category, in sorted order with `None` first.
+    pub fn schemes(&self) -> impl Iterator<Item = Option<&str>> {
+        self.schemes.keys().map(|scheme| scheme.as_ref().map(|s| &s[..]))
+    }
+
+    /// The categories in `scheme`, sorted by their folded term.
+    pub fn categories(&self, scheme: Option<&str>) -> impl Iterator<Item = &IndexedCategory<'a>> {
+        self.schemes.get(&scheme.map(String::from)).into_iter().flat_map(|terms| terms.values())
+    }
+
+    /// Every category in every scheme.
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedCategory<'a>> {
+        self.schemes.values().flat_map(|terms| terms.values())
+    }
+
+    /// Looks up a category, folding `term` in the same way as when the index was built.
+    pub fn get(&self, scheme: Option<&str>, term: &str) -> Option<&IndexedCategory<'a>> {
+        self.schemes.get(&scheme.map(String::from))?.get(&self.case_folding.fold(term))
+    }
+
+    /// The entries with a category, which is empty when no entry has it.
+    pub fn entries(&self, scheme: Option<&str>, term: &str) -> &[&'a Entry] {
+        self.get(scheme, term).map_or(&[], |indexed| &indexed.entries[..])
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{CaseFolding, CategoryIndex, IndexOptions, LabelMerge};
+    use ::{Category, Entry, Feed};
+
+    fn feed() -> Feed {
+        let category = |term: &str, label: Option<&str>| Category {
+            term: term.to_string(),
+            label: label.map(String::from),
+            ..Default::default()
+        };
+
+        let entry = |id: &str, categories| Entry { id: id.to_string(), categories, ..Default::default() };
+
+        Feed {
+            entries: vec![
+                entry("1", vec![category("ml", Some("ML"))]),
+                entry("2", vec![category("ML", Some("Machine learning")), category("ml", None)]),
+                entry("3", vec![category("ml", Some("Machine learning"))]),
+                entry("4", vec![category("Ümlaut", None)]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_case_folding() {
+        let feed = feed();
+
+        let exact = CategoryIndex::new(&feed, &IndexOptions::default());
+        assert_eq!(exact.entries(None, "ml").len(), 3);
+        assert_eq!(exact.entries(None, "ML").len(), 1);
+
+        let ascii = CategoryIndex::new(&feed, &IndexOptions {
+            case_folding: CaseFolding::AsciiLowercase,
+            ..Default::default()
+        });
+        let ml = ascii.get(None, "Ml").unwrap();
+        assert_eq!(ml.term, "ml");
+        assert_eq!(ml.entries.iter().map(|e| &e.id[..]).collect::<Vec<_>>(), vec!["1", "2", "3"]);
+        assert!(ascii.get(None, "ümlaut").is_none());
+
+        let unicode = CategoryIndex::new(&feed, &IndexOptions {
+            case_folding: CaseFolding::Lowercase,
+            ..Default::default()
+        });
+        assert_eq!(unicode.get(None, "ümlaut").unwrap().display_name(), "Ümlaut");
+        assert_eq!(unicode.schemes().collect::<Vec<_>>(), vec![None]);
+        assert_eq!(unicode.categories(None).count(), 2);
+    }
+
+    #[test]
+    fn test_label_merge() {
+        let feed = feed();
+
+        let label = |label_merge| {
+            let options = IndexOptions { case_folding: CaseFolding::AsciiLowercase, label_merge };
+            let index = CategoryIndex::new(&feed, &options);
+            let label = index.get(None, "ml").unwrap().label.clone();
+            label
+        };
+
+        assert_eq!(label(LabelMerge::First), Some("ML".to_string()));
+        assert_eq!(label(LabelMerge::MostCommon), Some("Machine learning".to_string()));
+        assert_eq!(label(LabelMerge::Longest), Some("Machine learning".to_string()));
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+
+
+/// Parses an RFC 3339 (Atom, JSON Feed) or RFC 2822 (RSS) date.
+pub fn parse(s: &str) -> Option<DateTime<FixedOffset>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_rfc2822(s))
+        .ok()
+}
+
+
+/// Whether the date `new` is later than `old`. When either isn't a valid date, any change to the
+/// text counts as later.
+pub fn is_later(new: &str, old: &str) -> bool {
+    match (parse(new), parse(old)) {
+        (Some(new), Some(old)) => new > old,
+        _ => new.trim() != old.trim(),
+    }
+}
+
+
+/// Whether `a` and `b` are the same instant, even if written differently. When either isn't a
+/// valid date, their text is compared.
+pub fn is_same(a: &str, b: &str) -> bool {
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim() == b.trim(),
+    }
+}
+
+
+/// Rewrites a date in any supported format as an RFC 3339 timestamp, leaving unparseable input
+/// as it was.
+pub fn normalize(s: &str) -> String {
+    match parse(s) {
+        Some(date) => date.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        None => s.trim().to_string(),
+    }
+}
+
+
+/// Returns whichever of two dates is later, preferring ones that parse.
+pub fn latest(a: String, b: String) -> String {
+    match (parse(&a), parse(&b)) {
+        (Some(x), Some(y)) => if y > x { b } else { a },
+        (None, Some(_)) => b,
+        _ => a,
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use ::{Entry, Feed};
+use ::date;
+
+
+/// A part of a feed's metadata that can change between two snapshots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeedField {
+    Title,
+    Subtitle,
+    Updated,
+    Rights,
+    Icon,
+    Logo,
+    Generator,
+    Links,
+    Categories,
+    Authors,
+    Contributors,
+}
+
+
+/// A part of an entry that can change when it is updated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntryField {
+    Title,
+    Summary,
+    Content,
+    Links,
+    Categories,
+}
+
+
+/// An entry in both snapshots whose `updated` moved forward.
+#[derive(Clone)]
+pub struct EntryChange<'a> {
+    pub old: &'a Entry,
+    pub new: &'a Entry,
+    /// The fields that differ, which is empty when only `updated` changed
+    pub fields: Vec<EntryField>,
+}
+
+
+/// The differences between two snapshots of a feed, from `Feed::diff`.
+#[derive(Clone)]
+pub struct FeedDiff<'a> {
+    /// Entries only in the new snapshot, in its order
+    pub added: Vec<&'a Entry>,
+    /// Entries only in the old snapshot, in its order
+    pub removed: Vec<&'a Entry>,
+    /// Entries in both snapshots that were updated, in the new snapshot's order
+    pub updated: Vec<EntryChange<'a>>,
+    /// The metadata fields of the feed that differ
+    pub feed: Vec<FeedField>,
+    /// Entries in the new snapshot with no id, which can't be matched to the old snapshot and so
+    /// are in none of the lists above
+    pub unidentified: Vec<&'a Entry>,
+}
+
+impl<'a> FeedDiff<'a> {
+    pub fn new(old: &'a Feed, new: &'a Feed) -> FeedDiff<'a> {
+        let old_by_id = by_id(old);
+        let new_by_id = by_id(new);
+
+        let mut added = Vec::new();
+        let mut updated = Vec::new();
+        let mut unidentified = Vec::new();
+
+        for entry in &new.entries {
+            if entry.id.trim().is_empty() {
+                unidentified.push(entry);
+                continue;
+            }
+
+            match old_by_id.get(&entry.id[..]) {
+                Some(old_entry) if date::is_later(&entry.updated, &old_entry.updated) => {
+                    updated.push(EntryChange {
+                        old: old_entry,
+                        new: entry,
+                        fields: entry_fields(old_entry, entry),
+                    });
+                }
+                Some(_) => {}
+                None => added.push(entry),
+            }
+        }
+
+        let removed = old.entries.iter()
+            .filter(|entry| !entry.id.trim().is_empty() && !new_by_id.contains_key(&entry.id[..]))
+            .collect();
+
+        FeedDiff {
+            added,
+            removed,
+            updated,
+            feed: feed_fields(old, new),
+            unidentified,
+        }
+    }
+
+    /// Whether nothing changed that `diff` looks at. Entries without an id aren't counted.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.updated.is_empty() && self.feed.is_empty()
+    }
+}
+
+
+/// The entries of `feed` that have an id, by id.
+fn by_id(feed: &Feed) -> HashMap<&str, &Entry> {
+    feed.entries.iter()
+        .filter(|entry| !entry.id.trim().is_empty())
+        .map(|entry| (&entry.id[..], entry))
+        .collect()
+}
+
+
+fn entry_fields(old: &Entry, new: &Entry) -> Vec<EntryField> {
+    let mut fields = Vec::new();
+
+    if old.title != new.title {
+        fields.push(EntryField::Title);
+    }
+    if old.summary != new.summary {
+        fields.push(EntryField::Summary);
+    }
+    if old.content != new.content {
+        fields.push(EntryField::Content);
+    }
+    if old.links != new.links {
+        fields.push(EntryField::Links);
+    }
+    if old.categories != new.categories {
+        fields.push(EntryField::Categories);
+    }
+
+    fields
+}
+
+
+fn feed_fields(old: &Feed, new: &Feed) -> Vec<FeedField> {
+    let mut fields = Vec::new();
+
+    if old.title != new.title {
+        fields.push(FeedField::Title);
+    }
+    if old.subtitle != new.subtitle {
+        fields.push(FeedField::Subtitle);
+    }
+    if !date::is_same(&old.updated, &new.updated) {
+        fields.push(FeedField::Updated);
+    }
+    if old.rights != new.rights {
+        fields.push(FeedField::Rights);
+    }
+    if old.icon != new.icon {
+        fields.push(FeedField::Icon);
+    }
+    if old.logo != new.logo {
+        fields.push(FeedField::Logo);
+    }
+    if old.generator != new.generator {
+        fields.push(FeedField::Generator);
+    }
+    if old.links != new.links {
+        fields.push(FeedField::Links);
+    }
+    if old.categories != new.categories {
+        fields.push(FeedField::Categories);
+    }
+    if old.authors != new.authors {
+        fields.push(FeedField::Authors);
+    }
+    if old.contributors != new.contributors {
+        fields.push(FeedField::Contributors);
+    }
+
+    fields
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{EntryField, FeedField};
+    use ::{Category, Entry, Feed};
+
+    fn entry(id: &str, updated: &str, title: &str) -> Entry {
+        Entry {
+            id: id.to_string(),
+            updated: updated.to_string(),
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = Feed {
+            title: "Blog".into(),
+            updated: "2017-06-01T00:00:00Z".to_string(),
+            entries: vec![
+                entry("urn:1", "2017-06-01T00:00:00Z", "First"),
+                entry("urn:2", "2017-06-01T00:00:00Z", "Second"),
+                entry("urn:3", "2017-06-01T00:00:00Z", "Third"),
+                entry("urn:4", "2017-06-01T00:00:00Z", "Fourth"),
+            ],
+            ..Default::default()
+        };
+
+        let mut retagged = entry("urn:3", "2017-06-02T00:00:00+00:00", "Third");
+        retagged.categories.push(Category { term: "news".to_string(), ..Default::default() });
+
+        let new = Feed {
+            title: "Blog".into(),
+            updated: "2017-06-02T00:00:00Z".to_string(),
+            entries: vec![
+                entry("urn:5", "2017-06-02T00:00:00Z", "Fifth"),
+                // Edited without moving `updated` forward, so not reported
+                entry("urn:2", "2017-06-01T00:00:00Z", "Second, edited"),
+                retagged,
+                entry("urn:4", "2017-06-03T00:00:00Z", "Fourth, edited"),
+            ],
+            ..Default::default()
+        };
+
+        let diff = Feed::diff(&old, &new);
+
+        assert_eq!(diff.added.iter().map(|e| &e.id[..]).collect::<Vec<_>>(), vec!["urn:5"]);
+        assert_eq!(diff.removed.iter().map(|e| &e.id[..]).collect::<Vec<_>>(), vec!["urn:1"]);
+        assert_eq!(diff.updated.iter().map(|c| &c.new.id[..]).collect::<Vec<_>>(), vec!["urn:3", "urn:4"]);
+        assert_eq!(diff.updated[0].fields, vec![EntryField::Categories]);
+        assert_eq!(diff.updated[1].fields, vec![EntryField::Title]);
+        assert_eq!(diff.feed, vec![FeedField::Updated]);
+
+        assert!(Feed::diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_diff_updated_in_another_offset() {
+        let old = Feed { updated: "2017-06-01T12:00:00Z".to_string(), ..Default::default() };
+        let new = Feed { updated: "2017-06-01T14:00:00+02:00".to_string(), ..Default::default() };
+        assert!(Feed::diff(&old, &new).is_empty());
+
+        let new = Feed { updated: "2017-06-01T14:00:00Z".to_string(), ..Default::default() };
+        assert_eq!(Feed::diff(&old, &new).feed, vec![FeedField::Updated]);
+    }
+
+    #[test]
+    fn test_diff_entries_without_id() {
+        let old = Feed {
+            entries: vec![entry("", "2017-06-01T00:00:00Z", "First"), entry("urn:2", "2017-06-01T00:00:00Z", "Second")],
+            ..Default::default()
+        };
+        let new = Feed {
+            entries: vec![entry("", "2017-06-02T00:00:00Z", "Third"), entry(" ", "2017-06-02T00:00:00Z", "Fourth")],
+            ..Default::default()
+        };
+
+        let diff = Feed::diff(&old, &new);
+        assert!(diff.added.is_empty());
+        assert!(diff.updated.is_empty());
+        assert_eq!(diff.removed.iter().map(|e| &e.id[..]).collect::<Vec<_>>(), vec!["urn:2"]);
+        assert_eq!(diff.unidentified.iter().map(|e| e.title.as_str()).collect::<Vec<_>>(), vec!["Third", "Fourth"]);
+    }
+}
@@ -0,0 +1,159 @@
+use std::ops::Deref;
+
+use ::{Entry, Link, MediaType};
+use ::extension::media::MediaContent;
+
+
+/// An `enclosure` link, together with the entry it belongs to.
+///
+/// Besides the link itself, which it dereferences to, an enclosure gathers what the entry's
+/// extensions say about the linked file: Media RSS `<media:content>` elements with the same URL,
+/// and for a single enclosure, the iTunes duration of the episode.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::Feed;
+///
+/// let atom_str = r#"
+/// <feed xmlns="http://www.w3.org/2005/Atom"
+///       xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+///   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+///   <title>My Podcast</title>
+///   <updated>2019-04-01T07:30:00Z</updated>
+///   <entry>
+///     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+///     <title>Episode 1</title>
+///     <updated>2019-04-01T07:30:00Z</updated>
+///     <link rel="enclosure" href="http://test.blog/1.mp3" type="audio/mpeg" length="24986239"/>
+///     <itunes:duration>45:00</itunes:duration>
+///   </entry>
+/// </feed>
+/// "#;
+///
+/// let feed = atom_str.parse::<Feed>().unwrap();
+/// let enclosure = feed.enclosures().next().unwrap();
+///
+/// assert_eq!(enclosure.url(), "http://test.blog/1.mp3");
+/// assert_eq!(enclosure.length(), Some(24986239));
+/// assert_eq!(enclosure.duration(), Some(2700));
+/// assert_eq!(enclosure.entry.title, "Episode 1");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Enclosure<'a> {
+    pub link: &'a Link,
+    pub entry: &'a Entry,
+}
+
+impl<'a> Enclosure<'a> {
+    /// The URL of the file, which is the link's `href`.
+    pub fn url(&self) -> &'a str {
+        &self.link.href
+    }
+
+    /// The size of the file in bytes, from the link or else from Media RSS.
+    pub fn length(&self) -> Option<u64> {
+        self.link.length.as_ref().and_then(|l| l.bytes())
+            .or_else(|| self.media_content().and_then(|c| c.file_size))
+    }
+
+    /// The media type of the file, from the link's `type`.
+    pub fn mediatype(&self) -> Option<&'a MediaType> {
+        self.link.mediatype.as_ref()
+    }
+
+    /// The link's title, or else the entry's.
+    pub fn title(&self) -> &'a str {
+        match self.link.title {
+            Some(ref title) => title,
+            None => self.entry.title.as_str(),
+        }
+    }
+
+    /// The running time in seconds, from Media RSS, or else from iTunes if this is the entry's
+    /// only enclosure.
+    pub fn duration(&self) -> Option<u64> {
+        self.media_content().and_then(|c| c.duration).or_else(|| {
+            if self.entry.enclosures().count() == 1 {
+                self.entry.itunes.as_ref().and_then(|itunes| itunes.duration_seconds())
+            } else {
+                None
+            }
+        })
+    }
+
+    /// The `<media:content>` describing the same file, if there is one.
+    pub fn media_content(&self) -> Option<&'a MediaContent> {
+        let media = self.entry.media.as_ref()?;
+        media.all_contents().find(|c| c.url.as_ref() == Some(&self.link.href))
+    }
+}
+
+impl<'a> Deref for Enclosure<'a> {
+    type Target = Link;
+
+    fn deref(&self) -> &Link {
+        self.link
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ::{Entry, Feed, Length, Link, LinkRel};
+    use ::extension::itunes::ITunesEntryExtension;
+    use ::extension::media::{MediaContent, MediaExtension};
+
+    fn enclosure(href: &str, length: Option<Length>) -> Link {
+        Link {
+            href: href.to_string(),
+            rel: Some(LinkRel::Enclosure),
+            length,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_extension_metadata() {
+        let feed = Feed {
+            entries: vec![
+                Entry {
+                    title: "Episode 1".into(),
+                    links: vec![
+                        enclosure("http://test.blog/1.mp3", None),
+                        enclosure("http://test.blog/1.ogg", Some(Length::Bytes(2048))),
+                    ],
+                    media: Some(MediaExtension {
+                        contents: vec![
+                            MediaContent {
+                                url: Some("http://test.blog/1.mp3".to_string()),
+                                file_size: Some(1024),
+                                duration: Some(2701),
+                                ..Default::default()
+                            },
+                        ],
+                        ..Default::default()
+                    }),
+                    itunes: Some(ITunesEntryExtension {
+                        duration: Some("45:00".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let enclosures: Vec<_> = feed.enclosures().collect();
+        assert_eq!(enclosures.len(), 2);
+
+        assert_eq!(enclosures[0].length(), Some(1024));
+        assert_eq!(enclosures[0].duration(), Some(2701));
+        assert_eq!(enclosures[0].title(), "Episode 1");
+
+        // The iTunes duration is for the episode, so it can't be pinned on one of several files
+        assert_eq!(enclosures[1].length(), Some(2048));
+        assert_eq!(enclosures[1].duration(), None);
+        assert!(enclosures[1].media_content().is_none());
+    }
+}
@@ -0,0 +1,92 @@
+use std::borrow::Cow;
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+
+
+/// Decodes a document to UTF-8, returning the encoding it was read as.
+///
+/// The encoding is taken from, in order of precedence, a byte order mark, the `charset`
+/// parameter of the `Content-Type` it was served with (as [RFC 7303 § 3]
+/// (https://tools.ietf.org/html/rfc7303#section-3) requires), and the encoding declaration in
+/// the XML prolog, defaulting to UTF-8. Malformed sequences are replaced with U+FFFD.
+pub fn decode<'a>(bytes: &'a [u8], charset: Option<&str>) -> (Cow<'a, str>, &'static Encoding) {
+    let encoding = match Encoding::for_bom(bytes) {
+        Some((encoding, _)) => encoding,
+        None => charset.and_then(|label| Encoding::for_label(label.trim().as_bytes()))
+            .or_else(|| sniff_declaration(bytes))
+            .unwrap_or(UTF_8),
+    };
+
+    let (text, _) = encoding.decode_with_bom_removal(bytes);
+    (text, encoding)
+}
+
+
+/// Reads the `encoding` pseudo-attribute of `<?xml ...?>`, recognizing UTF-16 documents without a
+/// byte order mark by the layout of the leading `<?`.
+fn sniff_declaration(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.starts_with(b"<\0?\0") {
+        return Some(UTF_16LE);
+    }
+    if bytes.starts_with(b"\0<\0?") {
+        return Some(UTF_16BE);
+    }
+
+    if !bytes.starts_with(b"<?xml") {
+        return None;
+    }
+
+    let end = bytes.iter().position(|&b| b == b'>')?;
+    let declaration = String::from_utf8_lossy(&bytes[..end]);
+
+    let start = declaration.find("encoding")? + "encoding".len();
+    let rest = declaration[start..].trim_start().strip_prefix('=')?.trim_start();
+
+    let quote = rest.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    let label = rest[1..].split(quote).next()?;
+
+    // An ASCII-readable declaration claiming UTF-16 is wrong about itself.
+    match Encoding::for_label(label.as_bytes()) {
+        Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => Some(UTF_8),
+        encoding => encoding,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use encoding_rs::{SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+    use super::decode;
+
+    #[test]
+    fn test_decode_bom() {
+        let bytes = b"\xef\xbb\xbf<feed/>";
+        assert_eq!(decode(bytes, Some("iso-8859-1")), ("<feed/>".into(), UTF_8));
+
+        let bytes = b"\xff\xfe<\0f\0/\0>\0";
+        assert_eq!(decode(bytes, None), ("<f/>".into(), UTF_16LE));
+    }
+
+    #[test]
+    fn test_decode_declaration() {
+        let bytes = b"<?xml version='1.0' encoding = 'ISO-8859-1'?><title>caf\xe9</title>";
+        let (text, encoding) = decode(bytes, None);
+        assert_eq!(encoding, WINDOWS_1252);
+        assert!(text.ends_with("<title>caf\u{e9}</title>"));
+
+        let bytes = b"\0<\0?\0x\0m\0l\0?\0>";
+        assert_eq!(decode(bytes, None).1, UTF_16BE);
+
+        let bytes = b"<?xml version=\"1.0\" encoding=\"UTF-16\"?><a/>";
+        assert_eq!(decode(bytes, None).1, UTF_8);
+    }
+
+    #[test]
+    fn test_decode_charset_hint() {
+        let bytes = b"<?xml version=\"1.0\" encoding=\"utf-8\"?><title>\x93\xfa\x96\x7b</title>";
+        let (text, encoding) = decode(bytes, Some("Shift_JIS"));
+        assert_eq!(encoding, SHIFT_JIS);
+        assert!(text.ends_with("<title>\u{65e5}\u{672c}</title>"));
+    }
+}
@@ -1,6 +1,15 @@
+use std::borrow::Cow;
 use xml::Element;
 
-use ::{Author, Category, Contributor, ElementUtils, Feed, Link, NS, Person, ViaXml};
+use ::{Author, Category, Contributor, ElementUtils, Enclosure, FromScopedXml, Link, LinkPreferences, LinkRel, NS, Person, ResolveUris, Source, Text, ViaXml, ViaXmlChildren, XmlScope};
+use ::extension::activity::ActivityExtension;
+use ::extension::dublincore::{self, DublinCoreExtension};
+use ::extension::georss::GeoRssExtension;
+use ::extension::itunes::ITunesEntryExtension;
+use ::extension::media::MediaExtension;
+use ::extension::podcast::PodcastEntryExtension;
+use ::link;
+use ::text;
 
 
 /// [The Atom Syndication Format § The "atom:entry" Element]
@@ -13,24 +22,106 @@ use ::{Author, Category, Contributor, ElementUtils, Feed, Link, NS, Person, ViaX
 ///
 /// let entry = Entry {
 ///     id: String::from("9dd22af1-7298-4ca6-af40-6a44bae3726f"),
-///     title: String::from("A blog post title"),
+///     title: "A blog post title".into(),
 ///     updated: String::from("2015-05-11T21:30:54Z"),
 ///     ..Default::default()
 /// };
 /// ```
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Entry {
     pub id: String,
-    pub title: String,
+    pub title: Text,
     pub updated: String,
     pub published: Option<String>,
-    pub source: Option<Feed>,
+    pub source: Option<Source>,
     pub links: Vec<Link>,
     pub categories: Vec<Category>,
     pub authors: Vec<Person>,
     pub contributors: Vec<Person>,
-    pub summary: Option<String>,
-    pub content: Option<String>,
+    pub summary: Option<Text>,
+    pub content: Option<Text>,
+    pub rights: Option<Text>,
+    pub media: Option<MediaExtension>,
+    pub podcast: Option<PodcastEntryExtension>,
+    pub itunes: Option<ITunesEntryExtension>,
+    pub dublin_core: Option<DublinCoreExtension>,
+    pub georss: Option<GeoRssExtension>,
+    pub activity: Option<ActivityExtension>,
+    /// The effective `xml:base` of the entry, which IRIs inside it may be relative to
+    pub base: Option<String>,
+    /// The effective `xml:lang` of the entry
+    pub lang: Option<String>,
+}
+
+
+impl Entry {
+    /// Makes every IRI in the entry absolute, resolving them against their effective `xml:base`
+    /// and finally against `document_url`, the URL the entry was retrieved from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Entry, Link};
+    ///
+    /// let mut entry = Entry {
+    ///     links: vec![Link { href: String::from("posts/42"), ..Default::default() }],
+    ///     base: Some(String::from("/blog/")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// entry.resolve_relative_uris("http://example.com/feed.atom");
+    /// assert_eq!(entry.links[0].href, "http://example.com/blog/posts/42");
+    /// ```
+    pub fn resolve_relative_uris(&mut self, document_url: &str) {
+        self.resolve_uris(document_url, document_url);
+    }
+
+    /// The entry's authors, or if it has none, people named by its `dc:creator` elements.
+    pub fn authors_or_creators(&self) -> Cow<'_, [Person]> {
+        dublincore::authors_or_creators(&self.authors, self.dublin_core.as_ref())
+    }
+
+    /// `updated` if it is a valid date, otherwise `dcterms:modified` if that is.
+    pub fn updated_or_modified(&self) -> Option<&str> {
+        dublincore::updated_or_modified(&self.updated, self.dublin_core.as_ref())
+    }
+
+    /// The best link to the entry's page for `prefs`. Links with no `rel` count as `alternate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Entry, Link, LinkPreferences, LinkRel};
+    ///
+    /// let entry = Entry {
+    ///     links: vec![
+    ///         Link { href: String::from("http://test.blog/1.pdf"), mediatype: Some("application/pdf".into()), ..Default::default() },
+    ///         Link { href: String::from("http://test.blog/1"), rel: Some(LinkRel::Alternate), mediatype: Some("text/html".into()), ..Default::default() },
+    ///         Link { href: String::from("http://test.blog/fr/1"), hreflang: Some(String::from("fr")), ..Default::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(entry.alternate_link(&LinkPreferences::default()).unwrap().href, "http://test.blog/1");
+    ///
+    /// let french = LinkPreferences { hreflang: Some(String::from("fr")), ..Default::default() };
+    /// assert_eq!(entry.alternate_link(&french).unwrap().href, "http://test.blog/fr/1");
+    /// ```
+    pub fn alternate_link(&self, prefs: &LinkPreferences) -> Option<&Link> {
+        link::alternate(&self.links, prefs)
+    }
+
+    /// The `enclosure` links, to media such as podcast episodes.
+    pub fn enclosures(&self) -> impl Iterator<Item = Enclosure<'_>> {
+        self.links.iter()
+            .filter(|link| link.has_rel(&LinkRel::Enclosure))
+            .map(move |link| Enclosure { link, entry: self })
+    }
+
+    /// The link to edit the entry with the Atom Publishing Protocol.
+    pub fn edit_link(&self) -> Option<&Link> {
+        self.links.iter().find(|link| link.has_rel(&LinkRel::Edit))
+    }
 }
 
 
@@ -38,8 +129,11 @@ impl ViaXml for Entry {
     fn to_xml(&self) -> Element {
         let mut entry = Element::new("entry".to_string(), Some(NS.to_string()), vec![]);
 
+        entry.xml_attribute_with_optional_text("base", &self.base);
+        entry.xml_attribute_with_optional_text("lang", &self.lang);
+
         entry.tag_with_text("id", &self.id);
-        entry.tag_with_text("title", &self.title);
+        entry.tag_with_text_construct("title", &self.title);
         entry.tag_with_text("updated", &self.updated);
 
         entry.tag_with_optional_text("published", &self.published);
@@ -64,20 +158,54 @@ impl ViaXml for Entry {
             entry.tag(Contributor(person.clone()).to_xml());
         }
 
-        entry.tag_with_optional_text("summary", &self.summary);
-        entry.tag_with_optional_text("content", &self.content);
+        entry.tag_with_optional_text_construct("summary", &self.summary);
+        entry.tag_with_optional_text_construct("content", &self.content);
+        entry.tag_with_optional_text_construct("rights", &self.rights);
+
+        if let Some(ref media) = self.media {
+            media.append_xml(&mut entry);
+        }
+
+        if let Some(ref podcast) = self.podcast {
+            podcast.append_xml(&mut entry);
+        }
+
+        if let Some(ref itunes) = self.itunes {
+            itunes.append_xml(&mut entry);
+        }
+
+        if let Some(ref dublin_core) = self.dublin_core {
+            dublin_core.append_xml(&mut entry);
+        }
+
+        if let Some(ref georss) = self.georss {
+            georss.append_xml(&mut entry);
+        }
+
+        if let Some(ref activity) = self.activity {
+            activity.append_xml(&mut entry);
+        }
 
         entry
     }
 
     fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Entry::from_scoped_xml(&elem, &XmlScope::default())
+    }
+}
+
+
+impl FromScopedXml for Entry {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str> {
+        let scope = parent.of(elem);
+
         let id = match elem.get_child("id", Some(NS)) {
             Some(elem) => elem.content_str(),
             None => return Err("<entry> is missing required <id> element"),
         };
 
         let title = match elem.get_child("title", Some(NS)) {
-            Some(elem) => elem.content_str(),
+            Some(elem) => text::from_xml(elem, &scope),
             None => return Err("<entry> is missing required <title> element"),
         };
 
@@ -86,44 +214,81 @@ impl ViaXml for Entry {
             None => return Err("<entry> is missing required <updated> element"),
         };
         
-        let source = elem.get_child("source", Some(NS)).map(|e| ViaXml::from_xml(e.clone()).unwrap());
+        let source = elem.get_child("source", Some(NS)).map(|e| FromScopedXml::from_scoped_xml(e, &scope)).transpose()?;
 
         let links = elem.get_children("link", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let categories = elem.get_children("category", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| ViaXml::from_xml(e.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let authors = elem.get_children("author", Some(NS))
-            .map(|e| {
-                let Author(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Author(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let contributors = elem.get_children("contributor", Some(NS))
-            .map(|e| {
-                let Contributor(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Contributor(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let published = elem.get_child("published", Some(NS)).map(Element::content_str);
-        let summary = elem.get_child("summary", Some(NS)).map(Element::content_str);
-        let content = elem.get_child("content", Some(NS)).map(Element::content_str);
+        let summary = elem.get_child("summary", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let content = elem.get_child("content", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let rights = elem.get_child("rights", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let media = MediaExtension::from_xml_children(elem);
+        let podcast = PodcastEntryExtension::from_xml_children(elem);
+        let itunes = ITunesEntryExtension::from_xml_children(elem);
+        let dublin_core = DublinCoreExtension::from_xml_children(elem);
+        let georss = GeoRssExtension::from_xml_children(elem);
+        let activity = ActivityExtension::from_xml_children(elem);
+        let base = scope.base.clone();
+        let lang = scope.lang();
 
         Ok(Entry {
-            id: id,
-            title: title,
-            updated: updated,
-            published: published,
-            source: source,
-            links: links,
-            categories: categories,
-            authors: authors,
-            contributors: contributors,
-            summary: summary,
-            content: content,
+            id,
+            title,
+            updated,
+            published,
+            source,
+            links,
+            categories,
+            authors,
+            contributors,
+            summary,
+            content,
+            rights,
+            media,
+            podcast,
+            itunes,
+            dublin_core,
+            georss,
+            activity,
+            base,
+            lang,
         })
     }
 }
+
+
+impl ResolveUris for Entry {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+
+        if let Some(ref mut source) = self.source {
+            source.resolve_uris(document_url, &base);
+        }
+
+        for link in &mut self.links {
+            link.resolve_uris(document_url, &base);
+        }
+
+        for person in self.authors.iter_mut().chain(self.contributors.iter_mut()) {
+            person.resolve_uris(document_url, &base);
+        }
+
+        if let Some(ref mut content) = self.content {
+            ::resolve_optional(&mut content.src, &base);
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! [Activity Streams 1.0](http://activitystrea.ms/specs/atom/1.0/) elements, which describe an
+//! entry as an action that someone took.
+//!
+//! The entry's author is the actor, `activity:verb` is what they did, and `activity:object` and
+//! `activity:target` are what they did it to and where. Verbs and object types are IRIs; the
+//! ones defined by the Activity Streams base schema are available as `Verb` and `ObjectType`
+//! variants, and any others are kept as `Other`.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//! use atom_syndication::extension::activity::{ObjectType, Verb};
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom" xmlns:activity="http://activitystrea.ms/spec/1.0/">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>Activity</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <entry>
+//!     <id>tag:test.blog,2019:activity/1</id>
+//!     <title>N. Blogger shared a photo</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <activity:verb>http://activitystrea.ms/schema/1.0/share</activity:verb>
+//!     <activity:object>
+//!       <activity:object-type>http://activitystrea.ms/schema/1.0/photo</activity:object-type>
+//!       <id>tag:example.com,2019:photo/42</id>
+//!       <title>Sunset</title>
+//!     </activity:object>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//! let activity = feed.entries[0].activity.as_ref().unwrap();
+//!
+//! assert_eq!(activity.verb(), Verb::Share);
+//!
+//! let object = activity.object.as_ref().unwrap();
+//! assert_eq!(object.object_type, Some(ObjectType::Photo));
+//! assert_eq!(object.title.as_ref().unwrap(), "Sunset");
+//! ```
+
+use xml::Element;
+
+use ::{ElementUtils, Link, NS, Text, ViaXml, ViaXmlChildren, XmlScope};
+use ::text;
+use super::{child_text, element, element_with_text};
+
+
+/// The Activity Streams namespace
+pub const NAMESPACE: &str = "http://activitystrea.ms/spec/1.0/";
+/// The prefix of the verb and object type IRIs defined by the base schema
+pub const SCHEMA: &str = "http://activitystrea.ms/schema/1.0/";
+const PREFIX: &str = "activity";
+
+
+/// The Activity Streams elements of an entry.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActivityExtension {
+    /// Every verb the entry declares, most specific first
+    pub verbs: Vec<Verb>,
+    /// The type of the entry itself, when the entry is the object of the activity
+    pub object_type: Option<ObjectType>,
+    pub object: Option<ActivityObject>,
+    pub target: Option<ActivityObject>,
+}
+
+impl ActivityExtension {
+    /// The verb of the activity, which is `Post` when none is given.
+    pub fn verb(&self) -> Verb {
+        self.verbs.first().cloned().unwrap_or(Verb::Post)
+    }
+}
+
+impl ViaXmlChildren for ActivityExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        for verb in &self.verbs {
+            parent.tag(element_with_text("verb", NAMESPACE, PREFIX, &verb.iri()));
+        }
+
+        if let Some(ref object_type) = self.object_type {
+            parent.tag(element_with_text("object-type", NAMESPACE, PREFIX, &object_type.iri()));
+        }
+
+        if let Some(ref object) = self.object {
+            parent.tag(object.to_xml("object"));
+        }
+
+        if let Some(ref target) = self.target {
+            parent.tag(target.to_xml("target"));
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = ActivityExtension {
+            verbs: parent.get_children("verb", Some(NAMESPACE))
+                .map(|e| Verb::from_iri(e.content_str().trim()))
+                .collect(),
+            object_type: child_text(parent, "object-type", NAMESPACE).map(|s| ObjectType::from_iri(&s)),
+            object: parent.get_child("object", Some(NAMESPACE)).map(ActivityObject::from_xml),
+            target: parent.get_child("target", Some(NAMESPACE)).map(ActivityObject::from_xml),
+        };
+
+        if extension == ActivityExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// `<activity:object>` or `<activity:target>`, which describe a thing using Atom elements.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ActivityObject {
+    pub object_type: Option<ObjectType>,
+    pub id: Option<String>,
+    pub title: Option<Text>,
+    pub summary: Option<Text>,
+    pub content: Option<Text>,
+    pub links: Vec<Link>,
+    pub published: Option<String>,
+    pub updated: Option<String>,
+}
+
+impl ActivityObject {
+    fn to_xml(&self, name: &str) -> Element {
+        let mut elem = element(name, NAMESPACE, PREFIX);
+
+        if let Some(ref object_type) = self.object_type {
+            elem.tag(element_with_text("object-type", NAMESPACE, PREFIX, &object_type.iri()));
+        }
+
+        elem.tag_with_optional_text("id", &self.id);
+        elem.tag_with_optional_text_construct("title", &self.title);
+        elem.tag_with_optional_text_construct("summary", &self.summary);
+        elem.tag_with_optional_text_construct("content", &self.content);
+
+        for link in &self.links {
+            elem.tag(link.to_xml());
+        }
+
+        elem.tag_with_optional_text("published", &self.published);
+        elem.tag_with_optional_text("updated", &self.updated);
+
+        elem
+    }
+
+    fn from_xml(elem: &Element) -> Self {
+        ActivityObject {
+            object_type: child_text(elem, "object-type", NAMESPACE).map(|s| ObjectType::from_iri(&s)),
+            id: elem.get_child("id", Some(NS)).map(Element::content_str),
+            title: elem.get_child("title", Some(NS)).map(|e| text::from_xml(e, &XmlScope::default())),
+            summary: elem.get_child("summary", Some(NS)).map(|e| text::from_xml(e, &XmlScope::default())),
+            content: elem.get_child("content", Some(NS)).map(|e| text::from_xml(e, &XmlScope::default())),
+            links: elem.get_children("link", Some(NS))
+                .filter_map(|e| Link::from_xml(e.clone()).ok())
+                .collect(),
+            published: elem.get_child("published", Some(NS)).map(Element::content_str),
+            updated: elem.get_child("updated", Some(NS)).map(Element::content_str),
+        }
+    }
+}
+
+
+/// The verbs of the Activity Streams base schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verb {
+    Favorite,
+    Follow,
+    Like,
+    MakeFriend,
+    Join,
+    Play,
+    Post,
+    Save,
+    Share,
+    Tag,
+    Update,
+    /// Any other verb, by its full IRI
+    Other(String),
+}
+
+impl Verb {
+    /// Reads a verb from its IRI, or from its bare name as some publishers write it.
+    pub fn from_iri(iri: &str) -> Verb {
+        match iri.strip_prefix(SCHEMA).unwrap_or(iri) {
+            "favorite" => Verb::Favorite,
+            "follow" => Verb::Follow,
+            "like" => Verb::Like,
+            "make-friend" => Verb::MakeFriend,
+            "join" => Verb::Join,
+            "play" => Verb::Play,
+            "post" => Verb::Post,
+            "save" => Verb::Save,
+            "share" => Verb::Share,
+            "tag" => Verb::Tag,
+            "update" => Verb::Update,
+            _ => Verb::Other(iri.to_string()),
+        }
+    }
+
+    /// The full IRI of the verb.
+    pub fn iri(&self) -> String {
+        let name = match *self {
+            Verb::Favorite => "favorite",
+            Verb::Follow => "follow",
+            Verb::Like => "like",
+            Verb::MakeFriend => "make-friend",
+            Verb::Join => "join",
+            Verb::Play => "play",
+            Verb::Post => "post",
+            Verb::Save => "save",
+            Verb::Share => "share",
+            Verb::Tag => "tag",
+            Verb::Update => "update",
+            Verb::Other(ref iri) => return iri.clone(),
+        };
+
+        format!("{}{}", SCHEMA, name)
+    }
+}
+
+
+/// The object types of the Activity Streams base schema.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectType {
+    Article,
+    Audio,
+    Bookmark,
+    Comment,
+    File,
+    Folder,
+    Group,
+    List,
+    Note,
+    Person,
+    Photo,
+    PhotoAlbum,
+    Place,
+    Playlist,
+    Product,
+    Review,
+    Service,
+    Status,
+    Video,
+    /// Any other object type, by its full IRI
+    Other(String),
+}
+
+impl ObjectType {
+    /// Reads an object type from its IRI, or from its bare name as some publishers write it.
+    pub fn from_iri(iri: &str) -> ObjectType {
+        match iri.strip_prefix(SCHEMA).unwrap_or(iri) {
+            "article" => ObjectType::Article,
+            "audio" => ObjectType::Audio,
+            "bookmark" => ObjectType::Bookmark,
+            "comment" => ObjectType::Comment,
+            "file" => ObjectType::File,
+            "folder" => ObjectType::Folder,
+            "group" => ObjectType::Group,
+            "list" => ObjectType::List,
+            "note" => ObjectType::Note,
+            "person" => ObjectType::Person,
+            "photo" => ObjectType::Photo,
+            "photo-album" => ObjectType::PhotoAlbum,
+            "place" => ObjectType::Place,
+            "playlist" => ObjectType::Playlist,
+            "product" => ObjectType::Product,
+            "review" => ObjectType::Review,
+            "service" => ObjectType::Service,
+            "status" => ObjectType::Status,
+            "video" => ObjectType::Video,
+            _ => ObjectType::Other(iri.to_string()),
+        }
+    }
+
+    /// The full IRI of the object type.
+    pub fn iri(&self) -> String {
+        let name = match *self {
+            ObjectType::Article => "article",
+            ObjectType::Audio => "audio",
+            ObjectType::Bookmark => "bookmark",
+            ObjectType::Comment => "comment",
+            ObjectType::File => "file",
+            ObjectType::Folder => "folder",
+            ObjectType::Group => "group",
+            ObjectType::List => "list",
+            ObjectType::Note => "note",
+            ObjectType::Person => "person",
+            ObjectType::Photo => "photo",
+            ObjectType::PhotoAlbum => "photo-album",
+            ObjectType::Place => "place",
+            ObjectType::Playlist => "playlist",
+            ObjectType::Product => "product",
+            ObjectType::Review => "review",
+            ObjectType::Service => "service",
+            ObjectType::Status => "status",
+            ObjectType::Video => "video",
+            ObjectType::Other(ref iri) => return iri.clone(),
+        };
+
+        format!("{}{}", SCHEMA, name)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{ActivityExtension, ActivityObject, ObjectType, Verb};
+    use ::{Entry, Feed, Link, LinkRel};
+
+    #[test]
+    fn test_round_trip() {
+        let activity = ActivityExtension {
+            verbs: vec![Verb::Other("http://example.com/verbs/bookmark".to_string()), Verb::Save],
+            object_type: Some(ObjectType::Note),
+            object: Some(ActivityObject {
+                object_type: Some(ObjectType::Bookmark),
+                id: Some("tag:example.com,2019:bookmark/7".to_string()),
+                title: Some("Rust".into()),
+                links: vec![
+                    Link {
+                        href: "https://www.rust-lang.org/".to_string(),
+                        rel: Some(LinkRel::Related),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }),
+            target: Some(ActivityObject {
+                object_type: Some(ObjectType::Folder),
+                title: Some("Languages".into()),
+                ..Default::default()
+            }),
+        };
+
+        let feed = Feed {
+            entries: vec![Entry { activity: Some(activity.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("<activity:verb>http://activitystrea.ms/schema/1.0/save</activity:verb>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.entries[0].activity, Some(activity));
+    }
+
+    #[test]
+    fn test_iris() {
+        assert_eq!(Verb::from_iri("http://activitystrea.ms/schema/1.0/make-friend"), Verb::MakeFriend);
+        assert_eq!(Verb::from_iri("post"), Verb::Post);
+        assert_eq!(Verb::MakeFriend.iri(), "http://activitystrea.ms/schema/1.0/make-friend");
+        assert_eq!(ObjectType::from_iri("http://activitystrea.ms/schema/1.0/photo-album"), ObjectType::PhotoAlbum);
+        assert_eq!(ObjectType::from_iri("http://ostatus.org/schema/1.0/activity"),
+                   ObjectType::Other("http://ostatus.org/schema/1.0/activity".to_string()));
+        assert_eq!(ActivityExtension::default().verb(), Verb::Post);
+    }
+}
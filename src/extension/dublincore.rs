@@ -0,0 +1,242 @@
+//! [Dublin Core](https://www.dublincore.org/specifications/dublin-core/dcmi-terms/) elements,
+//! from both the legacy `dc:` namespace and the `dcterms:` namespace.
+//!
+//! `Entry` and `Feed` use these as fallbacks: `authors_or_creators` turns `dc:creator` into
+//! authors when there are no `<author>` elements, and `updated_or_modified` falls back to
+//! `dcterms:modified` when `<updated>` isn't a valid date.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom"
+//!       xmlns:dc="http://purl.org/dc/elements/1.1/"
+//!       xmlns:dcterms="http://purl.org/dc/terms/">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>Acquisitions</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <entry>
+//!     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+//!     <title>Letters, 1850-1870</title>
+//!     <updated>last week</updated>
+//!     <dc:creator>N. Archivist</dc:creator>
+//!     <dc:subject>Correspondence</dc:subject>
+//!     <dcterms:modified>2019-03-25T10:00:00Z</dcterms:modified>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//! let entry = &feed.entries[0];
+//!
+//! assert_eq!(entry.dublin_core.as_ref().unwrap().subjects, vec!["Correspondence"]);
+//! assert_eq!(entry.authors_or_creators()[0].name, "N. Archivist");
+//! assert_eq!(entry.updated_or_modified(), Some("2019-03-25T10:00:00Z"));
+//! ```
+
+use std::borrow::Cow;
+use xml::Element;
+
+use ::Person;
+use ::ViaXmlChildren;
+use ::date;
+use super::{child_text, element_with_text};
+
+
+/// The Dublin Core elements namespace
+pub const NAMESPACE: &str = "http://purl.org/dc/elements/1.1/";
+/// The DCMI terms namespace
+pub const TERMS_NAMESPACE: &str = "http://purl.org/dc/terms/";
+const PREFIX: &str = "dc";
+const TERMS_PREFIX: &str = "dcterms";
+
+
+/// The Dublin Core elements of an entry or feed.
+///
+/// Every `dc:` element may be repeated, so each is a `Vec` in document order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DublinCoreExtension {
+    pub titles: Vec<String>,
+    pub creators: Vec<String>,
+    pub subjects: Vec<String>,
+    pub descriptions: Vec<String>,
+    pub publishers: Vec<String>,
+    pub contributors: Vec<String>,
+    pub dates: Vec<String>,
+    pub types: Vec<String>,
+    pub formats: Vec<String>,
+    pub identifiers: Vec<String>,
+    pub sources: Vec<String>,
+    pub languages: Vec<String>,
+    pub relations: Vec<String>,
+    pub coverages: Vec<String>,
+    pub rights: Vec<String>,
+    /// `dcterms:created`
+    pub created: Option<String>,
+    /// `dcterms:issued`
+    pub issued: Option<String>,
+    /// `dcterms:modified`
+    pub modified: Option<String>,
+}
+
+impl DublinCoreExtension {
+    /// People made from the `dc:creator` elements, which only ever hold a name.
+    pub fn creators_as_people(&self) -> Vec<Person> {
+        self.creators.iter()
+            .map(|name| Person { name: name.clone(), ..Default::default() })
+            .collect()
+    }
+
+    fn elements(&self) -> [(&'static str, &Vec<String>); 15] {
+        [
+            ("title", &self.titles),
+            ("creator", &self.creators),
+            ("subject", &self.subjects),
+            ("description", &self.descriptions),
+            ("publisher", &self.publishers),
+            ("contributor", &self.contributors),
+            ("date", &self.dates),
+            ("type", &self.types),
+            ("format", &self.formats),
+            ("identifier", &self.identifiers),
+            ("source", &self.sources),
+            ("language", &self.languages),
+            ("relation", &self.relations),
+            ("coverage", &self.coverages),
+            ("rights", &self.rights),
+        ]
+    }
+}
+
+/// `authors`, or if there are none, people named by the `dc:creator` elements of `dublin_core`.
+pub(crate) fn authors_or_creators<'a>(authors: &'a [Person], dublin_core: Option<&DublinCoreExtension>) -> Cow<'a, [Person]> {
+    match dublin_core {
+        Some(dublin_core) if authors.is_empty() => Cow::Owned(dublin_core.creators_as_people()),
+        _ => Cow::Borrowed(authors),
+    }
+}
+
+
+/// `updated` if it is a valid date, otherwise the `dcterms:modified` of `dublin_core` if that is.
+pub(crate) fn updated_or_modified<'a>(updated: &'a str, dublin_core: Option<&'a DublinCoreExtension>) -> Option<&'a str> {
+    let modified = dublin_core.and_then(|dc| dc.modified.as_ref()).map(|date| &date[..]);
+
+    Some(updated).into_iter().chain(modified)
+        .find(|date| date::parse(date).is_some())
+}
+
+
+impl ViaXmlChildren for DublinCoreExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        for &(name, values) in self.elements().iter() {
+            for value in values {
+                parent.tag(element_with_text(name, NAMESPACE, PREFIX, value));
+            }
+        }
+
+        let terms = [("created", &self.created), ("issued", &self.issued), ("modified", &self.modified)];
+
+        for &(name, value) in terms.iter() {
+            if let Some(ref value) = *value {
+                parent.tag(element_with_text(name, TERMS_NAMESPACE, TERMS_PREFIX, value));
+            }
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let all = |name| -> Vec<String> {
+            parent.get_children(name, Some(NAMESPACE))
+                .map(|e| e.content_str().trim().to_string())
+                .collect()
+        };
+
+        let extension = DublinCoreExtension {
+            titles: all("title"),
+            creators: all("creator"),
+            subjects: all("subject"),
+            descriptions: all("description"),
+            publishers: all("publisher"),
+            contributors: all("contributor"),
+            dates: all("date"),
+            types: all("type"),
+            formats: all("format"),
+            identifiers: all("identifier"),
+            sources: all("source"),
+            languages: all("language"),
+            relations: all("relation"),
+            coverages: all("coverage"),
+            rights: all("rights"),
+            created: child_text(parent, "created", TERMS_NAMESPACE),
+            issued: child_text(parent, "issued", TERMS_NAMESPACE),
+            modified: child_text(parent, "modified", TERMS_NAMESPACE),
+        };
+
+        if extension == DublinCoreExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::DublinCoreExtension;
+    use ::{Entry, Feed, Person};
+
+    #[test]
+    fn test_round_trip() {
+        let dublin_core = DublinCoreExtension {
+            creators: vec!["N. Archivist".to_string(), "A. Librarian".to_string()],
+            subjects: vec!["Correspondence".to_string()],
+            rights: vec!["Public domain".to_string()],
+            identifiers: vec!["ark:/13030/tf5p30086k".to_string()],
+            modified: Some("2019-03-25T10:00:00Z".to_string()),
+            ..Default::default()
+        };
+
+        let feed = Feed {
+            dublin_core: Some(dublin_core.clone()),
+            entries: vec![Entry { dublin_core: Some(dublin_core.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("<dc:creator>N. Archivist</dc:creator>"));
+        assert!(atom_string.contains("<dcterms:modified>2019-03-25T10:00:00Z</dcterms:modified>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.dublin_core, Some(dublin_core.clone()));
+        assert_eq!(feed.entries[0].dublin_core, Some(dublin_core));
+    }
+
+    #[test]
+    fn test_fallbacks_prefer_atom() {
+        let entry = Entry {
+            updated: "2019-04-01T07:30:00Z".to_string(),
+            authors: vec![Person { name: "N. Blogger".to_string(), ..Default::default() }],
+            dublin_core: Some(DublinCoreExtension {
+                creators: vec!["N. Archivist".to_string()],
+                modified: Some("2019-03-25T10:00:00Z".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(entry.authors_or_creators()[0].name, "N. Blogger");
+        assert_eq!(entry.updated_or_modified(), Some("2019-04-01T07:30:00Z"));
+    }
+
+    #[test]
+    fn test_fallbacks_without_dublin_core() {
+        let feed = Feed { updated: "yesterday".to_string(), ..Default::default() };
+
+        assert!(feed.authors_or_creators().is_empty());
+        assert_eq!(feed.updated_or_modified(), None);
+    }
+}
@@ -0,0 +1,379 @@
+//! [GeoRSS](http://www.georss.org/) locations, in both the Simple and the GML encodings.
+//!
+//! GML geometries are read into the same `Geometry` values as the Simple ones, and every geometry
+//! is written back out using the Simple encoding.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//! use atom_syndication::extension::georss::{BoundingBox, Geometry, Point};
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom"
+//!       xmlns:georss="http://www.georss.org/georss"
+//!       xmlns:gml="http://www.opengis.net/gml">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>Incidents</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <entry>
+//!     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+//!     <title>Road closed</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <georss:point>45.256 -71.92</georss:point>
+//!   </entry>
+//!   <entry>
+//!     <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+//!     <title>Power cut</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <georss:where>
+//!       <gml:Point><gml:pos>51.5 -0.12</gml:pos></gml:Point>
+//!     </georss:where>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//!
+//! let geometries = &feed.entries[1].georss.as_ref().unwrap().geometries;
+//! assert_eq!(geometries[0], Geometry::Point(Point { lat: 51.5, lon: -0.12 }));
+//!
+//! let new_england = BoundingBox {
+//!     lower: Point { lat: 41.0, lon: -74.0 },
+//!     upper: Point { lat: 47.5, lon: -66.9 },
+//! };
+//! let titles: Vec<_> = feed.entries_intersecting(&new_england).map(|e| e.title.as_str()).collect();
+//! assert_eq!(titles, vec!["Road closed"]);
+//! ```
+
+use xml::{Element, Xml};
+
+use ::ViaXmlChildren;
+use super::element_with_text;
+
+
+/// The GeoRSS namespace
+pub const NAMESPACE: &str = "http://www.georss.org/georss";
+/// The GML namespace, used inside `<georss:where>`
+pub const GML_NAMESPACE: &str = "http://www.opengis.net/gml";
+const PREFIX: &str = "georss";
+
+
+/// The GeoRSS locations of an entry or feed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoRssExtension {
+    pub geometries: Vec<Geometry>,
+}
+
+impl GeoRssExtension {
+    /// Whether any of the geometries overlap `bounds`.
+    pub fn intersects(&self, bounds: &BoundingBox) -> bool {
+        self.geometries.iter()
+            .filter_map(Geometry::bounding_box)
+            .any(|b| b.intersects(bounds))
+    }
+}
+
+impl ViaXmlChildren for GeoRssExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        for geometry in &self.geometries {
+            parent.tag(geometry.to_xml());
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let geometries: Vec<Geometry> = parent.children.iter()
+            .filter_map(|child| match *child {
+                Xml::ElementNode(ref e) => Geometry::from_xml(e),
+                _ => None,
+            })
+            .collect();
+
+        if geometries.is_empty() {
+            None
+        } else {
+            Some(GeoRssExtension { geometries })
+        }
+    }
+}
+
+
+/// A WGS84 position in decimal degrees.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+
+/// A rectangle between two corners.
+///
+/// Boxes that cross the antimeridian are not supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BoundingBox {
+    /// The south-west corner
+    pub lower: Point,
+    /// The north-east corner
+    pub upper: Point,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, point: &Point) -> bool {
+        point.lat >= self.lower.lat && point.lat <= self.upper.lat &&
+            point.lon >= self.lower.lon && point.lon <= self.upper.lon
+    }
+
+    /// Whether the two boxes overlap, including only touching at an edge.
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.lower.lat <= other.upper.lat && other.lower.lat <= self.upper.lat &&
+            self.lower.lon <= other.upper.lon && other.lower.lon <= self.upper.lon
+    }
+}
+
+
+/// A single GeoRSS location.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Geometry {
+    /// `<georss:point>` or `<gml:Point>`
+    Point(Point),
+    /// `<georss:line>` or `<gml:LineString>`
+    Line(Vec<Point>),
+    /// `<georss:polygon>` or `<gml:Polygon>`, whose first and last points are the same
+    Polygon(Vec<Point>),
+    /// `<georss:box>` or `<gml:Envelope>`
+    Box(BoundingBox),
+}
+
+impl Geometry {
+    /// The smallest box containing the whole geometry, or `None` for a line with no points.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let points = match *self {
+            Geometry::Point(point) => return Some(BoundingBox { lower: point, upper: point }),
+            Geometry::Box(bounds) => return Some(bounds),
+            Geometry::Line(ref points) | Geometry::Polygon(ref points) => points,
+        };
+
+        let first = *points.first()?;
+
+        Some(points.iter().fold(BoundingBox { lower: first, upper: first }, |b, p| {
+            BoundingBox {
+                lower: Point { lat: b.lower.lat.min(p.lat), lon: b.lower.lon.min(p.lon) },
+                upper: Point { lat: b.upper.lat.max(p.lat), lon: b.upper.lon.max(p.lon) },
+            }
+        }))
+    }
+
+    fn to_xml(&self) -> Element {
+        let (name, points) = match *self {
+            Geometry::Point(point) => ("point", vec![point]),
+            Geometry::Line(ref points) => ("line", points.clone()),
+            Geometry::Polygon(ref points) => ("polygon", points.clone()),
+            Geometry::Box(bounds) => ("box", vec![bounds.lower, bounds.upper]),
+        };
+
+        element_with_text(name, NAMESPACE, PREFIX, &format_points(&points))
+    }
+
+    /// Reads a Simple geometry or a `<georss:where>`, returning `None` for anything else or for
+    /// geometries with the wrong number of coordinates.
+    fn from_xml(elem: &Element) -> Option<Geometry> {
+        if elem.ns.as_ref().map(|ns| &ns[..]) != Some(NAMESPACE) {
+            return None;
+        }
+
+        let points = || parse_points(&elem.content_str());
+
+        match &elem.name[..] {
+            "point" => single_point(points()?),
+            "line" => line(points()?),
+            "polygon" => polygon(points()?),
+            "box" => bounding_box(points()?),
+            "where" => elem.children.iter()
+                .filter_map(|child| match *child {
+                    Xml::ElementNode(ref e) => from_gml(e),
+                    _ => None,
+                })
+                .next(),
+            _ => None,
+        }
+    }
+}
+
+
+fn from_gml(elem: &Element) -> Option<Geometry> {
+    if elem.ns.as_ref().map(|ns| &ns[..]) != Some(GML_NAMESPACE) {
+        return None;
+    }
+
+    let gml_points = |parent: &Element, name: &str| {
+        parent.get_child(name, Some(GML_NAMESPACE)).and_then(|e| parse_points(&e.content_str()))
+    };
+
+    match &elem.name[..] {
+        "Point" => single_point(gml_points(elem, "pos")?),
+        "LineString" => line(gml_points(elem, "posList")?),
+        "Polygon" => {
+            let ring = elem.get_child("exterior", Some(GML_NAMESPACE))?
+                .get_child("LinearRing", Some(GML_NAMESPACE))?;
+            polygon(gml_points(ring, "posList")?)
+        }
+        "Envelope" => {
+            let mut corners = gml_points(elem, "lowerCorner")?;
+            corners.extend(gml_points(elem, "upperCorner")?);
+            bounding_box(corners)
+        }
+        _ => None,
+    }
+}
+
+
+fn single_point(points: Vec<Point>) -> Option<Geometry> {
+    if points.len() == 1 { Some(Geometry::Point(points[0])) } else { None }
+}
+
+
+fn line(points: Vec<Point>) -> Option<Geometry> {
+    if points.len() >= 2 { Some(Geometry::Line(points)) } else { None }
+}
+
+
+fn polygon(points: Vec<Point>) -> Option<Geometry> {
+    if points.len() >= 4 { Some(Geometry::Polygon(points)) } else { None }
+}
+
+
+fn bounding_box(points: Vec<Point>) -> Option<Geometry> {
+    if points.len() == 2 {
+        Some(Geometry::Box(BoundingBox { lower: points[0], upper: points[1] }))
+    } else {
+        None
+    }
+}
+
+
+/// Parses a whitespace separated list of latitude and longitude pairs.
+fn parse_points(s: &str) -> Option<Vec<Point>> {
+    let numbers = s.split_whitespace()
+        .map(|n| n.parse::<f64>().ok())
+        .collect::<Option<Vec<f64>>>()?;
+
+    if numbers.is_empty() || numbers.len() % 2 != 0 {
+        return None;
+    }
+
+    Some(numbers.chunks(2).map(|pair| Point { lat: pair[0], lon: pair[1] }).collect())
+}
+
+
+fn format_points(points: &[Point]) -> String {
+    points.iter()
+        .map(|p| format!("{} {}", p.lat, p.lon))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{BoundingBox, GeoRssExtension, Geometry, Point};
+    use ::{Entry, Feed};
+
+    fn point(lat: f64, lon: f64) -> Point {
+        Point { lat, lon }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let georss = GeoRssExtension {
+            geometries: vec![
+                Geometry::Point(point(45.256, -71.92)),
+                Geometry::Line(vec![point(45.256, -110.45), point(46.46, -109.48), point(43.84, -109.86)]),
+                Geometry::Polygon(vec![point(45.256, -110.45), point(46.46, -109.48), point(43.84, -109.86),
+                                       point(45.256, -110.45)]),
+                Geometry::Box(BoundingBox { lower: point(42.943, -71.032), upper: point(43.039, -69.856) }),
+            ],
+        };
+
+        let feed = Feed {
+            entries: vec![Entry { georss: Some(georss.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("<georss:point>45.256 -71.92</georss:point>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.entries[0].georss, Some(georss));
+    }
+
+    #[test]
+    fn test_read_gml() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom"
+                  xmlns:georss="http://www.georss.org/georss"
+                  xmlns:gml="http://www.opengis.net/gml">
+                <id></id>
+                <title></title>
+                <updated></updated>
+                <entry>
+                    <id></id>
+                    <title></title>
+                    <updated></updated>
+                    <georss:where>
+                        <gml:LineString><gml:posList>45.256 -110.45 46.46 -109.48</gml:posList></gml:LineString>
+                    </georss:where>
+                    <georss:where>
+                        <gml:Polygon><gml:exterior><gml:LinearRing>
+                            <gml:posList>45.256 -110.45 46.46 -109.48 43.84 -109.86 45.256 -110.45</gml:posList>
+                        </gml:LinearRing></gml:exterior></gml:Polygon>
+                    </georss:where>
+                    <georss:where>
+                        <gml:Envelope>
+                            <gml:lowerCorner>42.943 -71.032</gml:lowerCorner>
+                            <gml:upperCorner>43.039 -69.856</gml:upperCorner>
+                        </gml:Envelope>
+                    </georss:where>
+                    <georss:point>45.256</georss:point>
+                </entry>
+            </feed>"#;
+
+        let feed = Feed::from_str(atom_str).unwrap();
+        let geometries = &feed.entries[0].georss.as_ref().unwrap().geometries;
+
+        assert_eq!(geometries.len(), 3);
+        assert_eq!(geometries[0], Geometry::Line(vec![point(45.256, -110.45), point(46.46, -109.48)]));
+        assert_eq!(geometries[1].bounding_box(), Some(BoundingBox {
+            lower: point(43.84, -110.45),
+            upper: point(46.46, -109.48),
+        }));
+        assert_eq!(geometries[2], Geometry::Box(BoundingBox {
+            lower: point(42.943, -71.032),
+            upper: point(43.039, -69.856),
+        }));
+    }
+
+    #[test]
+    fn test_entries_intersecting() {
+        let entry = |title: &str, geometry| Entry {
+            title: title.into(),
+            georss: Some(GeoRssExtension { geometries: vec![geometry] }),
+            ..Default::default()
+        };
+
+        let feed = Feed {
+            entries: vec![
+                entry("inside", Geometry::Point(point(10.0, 10.0))),
+                entry("outside", Geometry::Point(point(30.0, 10.0))),
+                entry("crossing", Geometry::Line(vec![point(15.0, 25.0), point(15.0, 15.0)])),
+                Entry { title: "nowhere".into(), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        let bounds = BoundingBox { lower: point(0.0, 0.0), upper: point(20.0, 20.0) };
+        let titles: Vec<_> = feed.entries_intersecting(&bounds).map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["inside", "crossing"]);
+    }
+}
@@ -0,0 +1,387 @@
+//! [iTunes podcast](https://help.apple.com/itc/podcasts_connect/#/itcb54353390) elements.
+//!
+//! Episode audio is described by the entry's `rel="enclosure"` links rather than by anything in
+//! this namespace. [`validate::podcast`](../../validate/fn.podcast.html) checks a feed for the
+//! elements podcast directories require.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom"
+//!       xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>My Podcast</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <itunes:category text="Technology">
+//!     <itunes:category text="Podcasting"/>
+//!   </itunes:category>
+//!   <entry>
+//!     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+//!     <title>Episode 1</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <itunes:duration>1:02:03</itunes:duration>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//!
+//! let itunes = feed.itunes.unwrap();
+//! assert_eq!(itunes.categories[0].subcategories[0].text, "Podcasting");
+//!
+//! let episode = feed.entries[0].itunes.as_ref().unwrap();
+//! assert_eq!(episode.duration_seconds(), Some(3723));
+//! ```
+
+use xml::Element;
+
+use ::{ElementUtils, ViaXml, ViaXmlChildren};
+use super::{attribute, child_text, element, element_with_text};
+
+
+/// The iTunes podcast namespace
+pub const NAMESPACE: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
+const PREFIX: &str = "itunes";
+
+
+/// The iTunes elements describing a whole podcast.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ITunesFeedExtension {
+    pub author: Option<String>,
+    /// The URL of the podcast artwork
+    pub image: Option<String>,
+    pub explicit: Option<bool>,
+    pub categories: Vec<ITunesCategory>,
+    pub owner: Option<ITunesOwner>,
+    pub summary: Option<String>,
+    /// `episodic` or `serial`
+    pub podcast_type: Option<String>,
+    pub block: Option<bool>,
+}
+
+impl ViaXmlChildren for ITunesFeedExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        append_optional_text(parent, "author", &self.author);
+        append_image(parent, &self.image);
+        append_optional_text(parent, "explicit", &self.explicit.map(|b| b.to_string()));
+
+        for category in &self.categories {
+            parent.tag(category.to_xml());
+        }
+
+        if let Some(ref owner) = self.owner {
+            parent.tag(owner.to_xml());
+        }
+
+        append_optional_text(parent, "summary", &self.summary);
+        append_optional_text(parent, "type", &self.podcast_type);
+        append_optional_text(parent, "block", &self.block.map(yes_no));
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = ITunesFeedExtension {
+            author: child_text(parent, "author", NAMESPACE),
+            image: image(parent),
+            explicit: child_text(parent, "explicit", NAMESPACE).and_then(|s| parse_bool(&s)),
+            categories: parent.get_children("category", Some(NAMESPACE))
+                .filter_map(|e| ITunesCategory::from_xml(e.clone()).ok())
+                .collect(),
+            owner: parent.get_child("owner", Some(NAMESPACE))
+                .and_then(|e| ITunesOwner::from_xml(e.clone()).ok()),
+            summary: child_text(parent, "summary", NAMESPACE),
+            podcast_type: child_text(parent, "type", NAMESPACE),
+            block: child_text(parent, "block", NAMESPACE).and_then(|s| parse_bool(&s)),
+        };
+
+        if extension == ITunesFeedExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// The iTunes elements describing a single episode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ITunesEntryExtension {
+    pub author: Option<String>,
+    /// The URL of the episode artwork
+    pub image: Option<String>,
+    /// The running time, as `HH:MM:SS`, `MM:SS` or a number of seconds
+    pub duration: Option<String>,
+    pub explicit: Option<bool>,
+    pub episode: Option<u32>,
+    pub season: Option<u32>,
+    /// `full`, `trailer` or `bonus`
+    pub episode_type: Option<String>,
+    pub summary: Option<String>,
+    pub block: Option<bool>,
+}
+
+impl ITunesEntryExtension {
+    /// The running time in seconds, if `duration` is written as `S`, `M:SS` or `H:MM:SS`.
+    pub fn duration_seconds(&self) -> Option<u64> {
+        let duration = self.duration.as_ref()?;
+        let parts: Vec<&str> = duration.trim().split(':').collect();
+
+        match parts[..] {
+            [s] => number(s),
+            [m, s] => Some(minutes_or_seconds(m, 1)? * 60 + minutes_or_seconds(s, 2)?),
+            [h, m, s] => Some(number(h)? * 3600 + minutes_or_seconds(m, 2)? * 60 + minutes_or_seconds(s, 2)?),
+            _ => None,
+        }
+    }
+}
+
+impl ViaXmlChildren for ITunesEntryExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        append_optional_text(parent, "author", &self.author);
+        append_image(parent, &self.image);
+        append_optional_text(parent, "duration", &self.duration);
+        append_optional_text(parent, "explicit", &self.explicit.map(|b| b.to_string()));
+        append_optional_text(parent, "episode", &self.episode.map(|n| n.to_string()));
+        append_optional_text(parent, "season", &self.season.map(|n| n.to_string()));
+        append_optional_text(parent, "episodeType", &self.episode_type);
+        append_optional_text(parent, "summary", &self.summary);
+        append_optional_text(parent, "block", &self.block.map(yes_no));
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = ITunesEntryExtension {
+            author: child_text(parent, "author", NAMESPACE),
+            image: image(parent),
+            duration: child_text(parent, "duration", NAMESPACE),
+            explicit: child_text(parent, "explicit", NAMESPACE).and_then(|s| parse_bool(&s)),
+            episode: child_text(parent, "episode", NAMESPACE).and_then(|s| s.parse().ok()),
+            season: child_text(parent, "season", NAMESPACE).and_then(|s| s.parse().ok()),
+            episode_type: child_text(parent, "episodeType", NAMESPACE),
+            summary: child_text(parent, "summary", NAMESPACE),
+            block: child_text(parent, "block", NAMESPACE).and_then(|s| parse_bool(&s)),
+        };
+
+        if extension == ITunesEntryExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// `<itunes:category>`, which may contain subcategories.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ITunesCategory {
+    pub text: String,
+    pub subcategories: Vec<ITunesCategory>,
+}
+
+impl ViaXml for ITunesCategory {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("category", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("text", &self.text);
+
+        for subcategory in &self.subcategories {
+            elem.tag(subcategory.to_xml());
+        }
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let text = match elem.get_attribute("text", None) {
+            Some(text) => text.to_string(),
+            None => return Err(r#"<itunes:category> is missing required "text" attribute"#),
+        };
+
+        let subcategories = elem.get_children("category", Some(NAMESPACE))
+            .filter_map(|e| ITunesCategory::from_xml(e.clone()).ok())
+            .collect();
+
+        Ok(ITunesCategory {
+            text,
+            subcategories,
+        })
+    }
+}
+
+
+/// `<itunes:owner>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ITunesOwner {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl ViaXml for ITunesOwner {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("owner", NAMESPACE, PREFIX);
+
+        append_optional_text(&mut elem, "name", &self.name);
+        append_optional_text(&mut elem, "email", &self.email);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Ok(ITunesOwner {
+            name: child_text(&elem, "name", NAMESPACE),
+            email: child_text(&elem, "email", NAMESPACE),
+        })
+    }
+}
+
+
+fn append_optional_text(parent: &mut Element, name: &str, text: &Option<String>) {
+    if let Some(ref text) = *text {
+        parent.tag(element_with_text(name, NAMESPACE, PREFIX, text));
+    }
+}
+
+
+fn append_image(parent: &mut Element, href: &Option<String>) {
+    if let Some(ref href) = *href {
+        let mut elem = element("image", NAMESPACE, PREFIX);
+        elem.attribute_with_text("href", href);
+        parent.tag(elem);
+    }
+}
+
+
+fn image(parent: &Element) -> Option<String> {
+    parent.get_child("image", Some(NAMESPACE)).and_then(|e| attribute(e, "href"))
+}
+
+
+/// Apple has used `yes`/`no`, `explicit`/`clean` and `true`/`false` over the years.
+fn parse_bool(s: &str) -> Option<bool> {
+    match &s.trim().to_lowercase()[..] {
+        "true" | "yes" | "explicit" => Some(true),
+        "false" | "no" | "clean" => Some(false),
+        _ => None,
+    }
+}
+
+
+fn yes_no(b: bool) -> String {
+    if b { "Yes" } else { "No" }.to_string()
+}
+
+
+fn number(s: &str) -> Option<u64> {
+    if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+
+/// Parses the minutes or seconds of a duration, which have at least `min_digits` digits and are
+/// less than 60.
+fn minutes_or_seconds(s: &str, min_digits: usize) -> Option<u64> {
+    number(s).filter(|&n| s.len() >= min_digits && n < 60)
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{ITunesCategory, ITunesEntryExtension, ITunesFeedExtension, ITunesOwner};
+    use ::{Entry, Feed};
+
+    #[test]
+    fn test_round_trip() {
+        let itunes = ITunesFeedExtension {
+            author: Some("N. Blogger".to_string()),
+            image: Some("http://test.blog/artwork.jpg".to_string()),
+            explicit: Some(false),
+            categories: vec![
+                ITunesCategory {
+                    text: "Society & Culture".to_string(),
+                    subcategories: vec![
+                        ITunesCategory { text: "Documentary".to_string(), ..Default::default() },
+                    ],
+                },
+            ],
+            owner: Some(ITunesOwner {
+                name: Some("N. Blogger".to_string()),
+                email: Some("n@test.blog".to_string()),
+            }),
+            block: Some(true),
+            ..Default::default()
+        };
+
+        let episode = ITunesEntryExtension {
+            duration: Some("45:00".to_string()),
+            episode: Some(3),
+            season: Some(1),
+            episode_type: Some("full".to_string()),
+            ..Default::default()
+        };
+
+        let feed = Feed {
+            itunes: Some(itunes.clone()),
+            entries: vec![Entry { itunes: Some(episode.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("<itunes:category text='Society &amp; Culture'><itunes:category "));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.itunes, Some(itunes));
+        assert_eq!(feed.entries[0].itunes, Some(episode));
+        assert_eq!(feed.entries[0].itunes.as_ref().unwrap().duration_seconds(), Some(2700));
+    }
+
+    #[test]
+    fn test_explicit_values() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom"
+                  xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+                <id></id>
+                <title></title>
+                <updated></updated>
+                <itunes:explicit>clean</itunes:explicit>
+                <entry>
+                    <id></id>
+                    <title></title>
+                    <updated></updated>
+                    <itunes:explicit>Yes</itunes:explicit>
+                    <itunes:duration>soon</itunes:duration>
+                </entry>
+            </feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.itunes.unwrap().explicit, Some(false));
+
+        let episode = feed.entries[0].itunes.as_ref().unwrap();
+        assert_eq!(episode.explicit, Some(true));
+        assert_eq!(episode.duration_seconds(), None);
+    }
+
+    #[test]
+    fn test_duration_seconds() {
+        let duration_seconds = |duration: &str| {
+            ITunesEntryExtension { duration: Some(duration.to_string()), ..Default::default() }.duration_seconds()
+        };
+
+        assert_eq!(duration_seconds("3723"), Some(3723));
+        assert_eq!(duration_seconds("45:00"), Some(2700));
+        assert_eq!(duration_seconds("5:07"), Some(307));
+        assert_eq!(duration_seconds(" 1:02:03 "), Some(3723));
+        assert_eq!(duration_seconds("1:99"), None);
+        assert_eq!(duration_seconds("75:00"), None);
+        assert_eq!(duration_seconds("1:2"), None);
+        assert_eq!(duration_seconds("1:2:3"), None);
+        assert_eq!(duration_seconds("1:2:3:4"), None);
+        assert_eq!(duration_seconds("1::03"), None);
+        assert_eq!(duration_seconds("+5"), None);
+        assert_eq!(duration_seconds(""), None);
+    }
+}
@@ -0,0 +1,515 @@
+//! [Media RSS](https://www.rssboard.org/media-rss) elements.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom" xmlns:media="http://search.yahoo.com/mrss/">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>My Videos</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <entry>
+//!     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+//!     <title>A video</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <media:content url="http://test.blog/video.mp4" type="video/mp4" duration="120">
+//!       <media:thumbnail url="http://test.blog/video.jpg" width="320" height="180"/>
+//!     </media:content>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//! let media = feed.entries[0].media.as_ref().unwrap();
+//!
+//! assert_eq!(media.contents[0].duration, Some(120));
+//! assert_eq!(media.thumbnails().next().unwrap().url, "http://test.blog/video.jpg");
+//! ```
+
+use std::str::FromStr;
+
+use xml::Element;
+
+use ::{ElementUtils, ViaXml, ViaXmlChildren};
+use super::{attribute, element, element_with_text, from_children};
+
+
+/// The Media RSS namespace
+pub const NAMESPACE: &str = "http://search.yahoo.com/mrss/";
+const PREFIX: &str = "media";
+
+
+/// The Media RSS elements of an entry or feed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaExtension {
+    /// `<media:group>` elements, each holding alternative versions of the same media
+    pub groups: Vec<MediaGroup>,
+    /// `<media:content>` elements outside of any group
+    pub contents: Vec<MediaContent>,
+    /// Elements describing all of the media
+    pub metadata: MediaMetadata,
+}
+
+impl MediaExtension {
+    /// Every thumbnail, whether it describes the entry, a group or a single content.
+    pub fn thumbnails(&self) -> impl Iterator<Item = &MediaThumbnail> {
+        self.metadata.thumbnails.iter()
+            .chain(self.groups.iter().flat_map(|g| {
+                g.metadata.thumbnails.iter()
+                    .chain(g.contents.iter().flat_map(|c| c.metadata.thumbnails.iter()))
+            }))
+            .chain(self.contents.iter().flat_map(|c| c.metadata.thumbnails.iter()))
+    }
+
+    /// Every content, whether it is in a group or not.
+    pub fn all_contents(&self) -> impl Iterator<Item = &MediaContent> {
+        self.groups.iter().flat_map(|g| g.contents.iter()).chain(self.contents.iter())
+    }
+}
+
+impl ViaXmlChildren for MediaExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        for group in &self.groups {
+            parent.tag(group.to_xml());
+        }
+
+        for content in &self.contents {
+            parent.tag(content.to_xml());
+        }
+
+        self.metadata.append_xml(parent);
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let groups: Vec<MediaGroup> = from_children(parent, "group", NAMESPACE);
+        let contents: Vec<MediaContent> = from_children(parent, "content", NAMESPACE);
+        let metadata = MediaMetadata::from_xml_children(parent);
+
+        if groups.is_empty() && contents.is_empty() && metadata.is_none() {
+            return None;
+        }
+
+        Some(MediaExtension {
+            groups,
+            contents,
+            metadata: metadata.unwrap_or_default(),
+        })
+    }
+}
+
+
+/// The optional elements that can describe an entry, a group or a single content.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaMetadata {
+    pub title: Option<MediaText>,
+    pub description: Option<MediaText>,
+    pub thumbnails: Vec<MediaThumbnail>,
+    pub player: Option<MediaPlayer>,
+    pub credits: Vec<MediaCredit>,
+}
+
+impl ViaXmlChildren for MediaMetadata {
+    fn append_xml(&self, parent: &mut Element) {
+        if let Some(ref title) = self.title {
+            parent.tag(title.to_xml("title"));
+        }
+
+        if let Some(ref description) = self.description {
+            parent.tag(description.to_xml("description"));
+        }
+
+        for thumbnail in &self.thumbnails {
+            parent.tag(thumbnail.to_xml());
+        }
+
+        if let Some(ref player) = self.player {
+            parent.tag(player.to_xml());
+        }
+
+        for credit in &self.credits {
+            parent.tag(credit.to_xml());
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let metadata = MediaMetadata {
+            title: parent.get_child("title", Some(NAMESPACE)).map(MediaText::from_xml),
+            description: parent.get_child("description", Some(NAMESPACE)).map(MediaText::from_xml),
+            thumbnails: from_children(parent, "thumbnail", NAMESPACE),
+            player: from_children(parent, "player", NAMESPACE).into_iter().next(),
+            credits: from_children(parent, "credit", NAMESPACE),
+        };
+
+        if metadata == MediaMetadata::default() {
+            None
+        } else {
+            Some(metadata)
+        }
+    }
+}
+
+
+/// `<media:group>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaGroup {
+    pub contents: Vec<MediaContent>,
+    pub metadata: MediaMetadata,
+}
+
+impl ViaXml for MediaGroup {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("group", NAMESPACE, PREFIX);
+
+        for content in &self.contents {
+            elem.tag(content.to_xml());
+        }
+
+        self.metadata.append_xml(&mut elem);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Ok(MediaGroup {
+            contents: from_children(&elem, "content", NAMESPACE),
+            metadata: MediaMetadata::from_xml_children(&elem).unwrap_or_default(),
+        })
+    }
+}
+
+
+/// `<media:content>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaContent {
+    pub url: Option<String>,
+    /// The size of the file in bytes
+    pub file_size: Option<u64>,
+    /// The MIME type of the file
+    pub mediatype: Option<String>,
+    /// `image`, `audio`, `video`, `document` or `executable`
+    pub medium: Option<String>,
+    pub is_default: Option<bool>,
+    /// `sample`, `full` or `nonstop`
+    pub expression: Option<String>,
+    /// Kilobits per second
+    pub bitrate: Option<f64>,
+    pub framerate: Option<f64>,
+    /// Kilohertz
+    pub sampling_rate: Option<f64>,
+    pub channels: Option<u32>,
+    /// Seconds
+    pub duration: Option<u64>,
+    pub height: Option<u32>,
+    pub width: Option<u32>,
+    pub lang: Option<String>,
+    pub metadata: MediaMetadata,
+    /// Numeric and boolean attributes whose values couldn't be parsed, kept as written
+    pub invalid_attributes: Vec<(String, String)>,
+}
+
+impl ViaXml for MediaContent {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("content", NAMESPACE, PREFIX);
+
+        elem.attribute_with_optional_text("url", &self.url);
+        elem.attribute_with_optional_text("fileSize", &self.file_size.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("type", &self.mediatype);
+        elem.attribute_with_optional_text("medium", &self.medium);
+        elem.attribute_with_optional_text("isDefault", &self.is_default.map(|b| b.to_string()));
+        elem.attribute_with_optional_text("expression", &self.expression);
+        elem.attribute_with_optional_text("bitrate", &self.bitrate.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("framerate", &self.framerate.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("samplingrate", &self.sampling_rate.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("channels", &self.channels.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("duration", &self.duration.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("height", &self.height.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("width", &self.width.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("lang", &self.lang);
+
+        for (name, value) in &self.invalid_attributes {
+            elem.set_attribute(name.clone(), None, value.clone());
+        }
+
+        self.metadata.append_xml(&mut elem);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let mut invalid = Vec::new();
+
+        Ok(MediaContent {
+            url: elem.get_attribute("url", None).map(String::from),
+            file_size: kept_attribute(&elem, "fileSize", &mut invalid),
+            mediatype: elem.get_attribute("type", None).map(String::from),
+            medium: elem.get_attribute("medium", None).map(String::from),
+            is_default: kept_attribute(&elem, "isDefault", &mut invalid),
+            expression: elem.get_attribute("expression", None).map(String::from),
+            bitrate: kept_attribute(&elem, "bitrate", &mut invalid),
+            framerate: kept_attribute(&elem, "framerate", &mut invalid),
+            sampling_rate: kept_attribute(&elem, "samplingrate", &mut invalid),
+            channels: kept_attribute(&elem, "channels", &mut invalid),
+            duration: kept_attribute(&elem, "duration", &mut invalid),
+            height: kept_attribute(&elem, "height", &mut invalid),
+            width: kept_attribute(&elem, "width", &mut invalid),
+            lang: elem.get_attribute("lang", None).map(String::from),
+            metadata: MediaMetadata::from_xml_children(&elem).unwrap_or_default(),
+            invalid_attributes: invalid,
+        })
+    }
+}
+
+
+/// Parses an attribute like `attribute` does, adding it to `invalid` if it has a value that
+/// doesn't parse.
+fn kept_attribute<T: FromStr>(elem: &Element, name: &str, invalid: &mut Vec<(String, String)>) -> Option<T> {
+    let value = elem.get_attribute(name, None)?;
+    let parsed = value.trim().parse().ok();
+
+    if parsed.is_none() {
+        invalid.push((name.to_string(), value.to_string()));
+    }
+
+    parsed
+}
+
+
+/// `<media:title>` or `<media:description>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaText {
+    pub value: String,
+    /// `plain` or `html`
+    pub text_type: Option<String>,
+}
+
+impl MediaText {
+    fn to_xml(&self, name: &str) -> Element {
+        let mut elem = element_with_text(name, NAMESPACE, PREFIX, &self.value);
+        elem.attribute_with_optional_text("type", &self.text_type);
+        elem
+    }
+
+    fn from_xml(elem: &Element) -> Self {
+        MediaText {
+            value: elem.content_str(),
+            text_type: elem.get_attribute("type", None).map(String::from),
+        }
+    }
+}
+
+
+/// `<media:thumbnail>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaThumbnail {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The time offset of the thumbnail within the media, in NTP format
+    pub time: Option<String>,
+}
+
+impl ViaXml for MediaThumbnail {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("thumbnail", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("url", &self.url);
+        elem.attribute_with_optional_text("width", &self.width.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("height", &self.height.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("time", &self.time);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let url = match elem.get_attribute("url", None) {
+            Some(url) => url.to_string(),
+            None => return Err(r#"<media:thumbnail> is missing required "url" attribute"#),
+        };
+
+        Ok(MediaThumbnail {
+            url,
+            width: attribute(&elem, "width"),
+            height: attribute(&elem, "height"),
+            time: elem.get_attribute("time", None).map(String::from),
+        })
+    }
+}
+
+
+/// `<media:player>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaPlayer {
+    pub url: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ViaXml for MediaPlayer {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("player", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("url", &self.url);
+        elem.attribute_with_optional_text("width", &self.width.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("height", &self.height.map(|n| n.to_string()));
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let url = match elem.get_attribute("url", None) {
+            Some(url) => url.to_string(),
+            None => return Err(r#"<media:player> is missing required "url" attribute"#),
+        };
+
+        Ok(MediaPlayer {
+            url,
+            width: attribute(&elem, "width"),
+            height: attribute(&elem, "height"),
+        })
+    }
+}
+
+
+/// `<media:credit>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaCredit {
+    pub value: String,
+    pub role: Option<String>,
+    pub scheme: Option<String>,
+}
+
+impl ViaXml for MediaCredit {
+    fn to_xml(&self) -> Element {
+        let mut elem = element_with_text("credit", NAMESPACE, PREFIX, &self.value);
+
+        elem.attribute_with_optional_text("role", &self.role);
+        elem.attribute_with_optional_text("scheme", &self.scheme);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Ok(MediaCredit {
+            value: elem.content_str(),
+            role: elem.get_attribute("role", None).map(String::from),
+            scheme: elem.get_attribute("scheme", None).map(String::from),
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{MediaContent, MediaCredit, MediaExtension, MediaGroup, MediaMetadata, MediaText,
+                MediaThumbnail};
+    use ::{Entry, Feed};
+
+    #[test]
+    fn test_round_trip() {
+        let media = MediaExtension {
+            groups: vec![
+                MediaGroup {
+                    contents: vec![
+                        MediaContent {
+                            url: Some("http://test.blog/video-hd.mp4".to_string()),
+                            mediatype: Some("video/mp4".to_string()),
+                            is_default: Some(true),
+                            bitrate: Some(2500.5),
+                            height: Some(720),
+                            ..Default::default()
+                        },
+                        MediaContent {
+                            url: Some("http://test.blog/video-sd.mp4".to_string()),
+                            height: Some(360),
+                            ..Default::default()
+                        },
+                    ],
+                    metadata: MediaMetadata {
+                        thumbnails: vec![
+                            MediaThumbnail {
+                                url: "http://test.blog/video.jpg".to_string(),
+                                time: Some("12:05:01.123".to_string()),
+                                ..Default::default()
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                },
+            ],
+            metadata: MediaMetadata {
+                title: Some(MediaText {
+                    value: "Tom & Jerry".to_string(),
+                    text_type: Some("plain".to_string()),
+                }),
+                credits: vec![
+                    MediaCredit {
+                        value: "N. Blogger".to_string(),
+                        role: Some("producer".to_string()),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let feed = Feed {
+            entries: vec![Entry { media: Some(media.clone()), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("xmlns:media='http://search.yahoo.com/mrss/'"));
+        assert!(atom_string.contains("<media:group><media:content "));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.entries[0].media, Some(media));
+        assert_eq!(feed.media, None);
+    }
+
+    #[test]
+    fn test_invalid_elements_are_skipped() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xmlns:m="http://search.yahoo.com/mrss/">
+                <id></id>
+                <title></title>
+                <updated></updated>
+                <m:thumbnail width="10"/>
+                <m:thumbnail url="http://test.blog/logo.png" width="wide"/>
+            </feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        let thumbnails: Vec<_> = feed.media.as_ref().unwrap().thumbnails().collect();
+        assert_eq!(thumbnails.len(), 1);
+        assert_eq!(thumbnails[0].width, None);
+    }
+
+    #[test]
+    fn test_invalid_content_attributes_are_kept() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xmlns:m="http://search.yahoo.com/mrss/">
+                <id></id>
+                <title></title>
+                <updated></updated>
+                <m:content url="http://test.blog/video.mp4" duration="2:00" height="720" isDefault="yes"/>
+            </feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        let content = &feed.media.as_ref().unwrap().contents[0];
+        assert_eq!(content.duration, None);
+        assert_eq!(content.height, Some(720));
+        assert_eq!(content.invalid_attributes, vec![
+            ("isDefault".to_string(), "yes".to_string()),
+            ("duration".to_string(), "2:00".to_string()),
+        ]);
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("duration='2:00'"));
+        assert!(atom_string.contains("isDefault='yes'"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(&feed.media.as_ref().unwrap().contents[0], content);
+    }
+}
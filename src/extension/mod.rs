@@ -0,0 +1,137 @@
+//! Typed support for extension elements from other namespaces.
+//!
+//! Each namespace has its own module, and its data is attached to `Feed` and `Entry` through an
+//! `Option` field that is `None` when none of the namespace's elements are present.
+
+pub mod activity;
+pub mod dublincore;
+pub mod georss;
+pub mod itunes;
+pub mod media;
+pub mod opensearch;
+pub mod podcast;
+
+use std::collections::BTreeMap;
+use xml::{Element, Xml};
+
+
+/// Creates an element in an extension namespace, to be written with `prefix` where possible.
+fn element(name: &str, ns: &str, prefix: &str) -> Element {
+    let mut elem = Element::new(name.to_string(), Some(ns.to_string()), vec![]);
+    elem.prefixes.insert(ns.to_string(), prefix.to_string());
+    elem
+}
+
+
+fn element_with_text(name: &str, ns: &str, prefix: &str, text: &str) -> Element {
+    let mut elem = element(name, ns, prefix);
+    elem.text(text.to_string());
+    elem
+}
+
+
+fn child_text(elem: &Element, name: &str, ns: &str) -> Option<String> {
+    elem.get_child(name, Some(ns)).map(|e| e.content_str().trim().to_string())
+}
+
+
+/// Parses every `name` child in `ns`, skipping any that are invalid.
+fn from_children<T: ::ViaXml>(parent: &Element, name: &str, ns: &str) -> Vec<T> {
+    parent.get_children(name, Some(ns))
+        .filter_map(|e| T::from_xml(e.clone()).ok())
+        .collect()
+}
+
+
+fn attribute<T: ::std::str::FromStr>(elem: &Element, name: &str) -> Option<T> {
+    elem.get_attribute(name, None).and_then(|value| value.trim().parse().ok())
+}
+
+
+const XMLNS_NS: &str = "http://www.w3.org/2000/xmlns/";
+
+
+/// An element from a namespace this crate has no typed support for, kept so that it can be
+/// read by the application and written back out.
+///
+/// Only text and child elements are kept, so comments and processing instructions are dropped,
+/// and the text of an element with mixed content is joined together.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExtensionElement {
+    pub name: String,
+    pub ns: Option<String>,
+    /// The prefix to write the namespace with, if it is free
+    pub prefix: Option<String>,
+    /// Attributes keyed by name and namespace
+    pub attributes: BTreeMap<(String, Option<String>), String>,
+    pub value: Option<String>,
+    pub children: Vec<ExtensionElement>,
+}
+
+impl ExtensionElement {
+    /// The value of an attribute with no namespace.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(&(name.to_string(), None)).map(|value| &value[..])
+    }
+
+    /// The first child element with this name and namespace.
+    pub fn child(&self, name: &str, ns: Option<&str>) -> Option<&ExtensionElement> {
+        self.children.iter().find(|child| child.name == name && child.ns.as_ref().map(|ns| &ns[..]) == ns)
+    }
+
+    pub fn to_xml(&self) -> Element {
+        let mut elem = Element::new(self.name.clone(), self.ns.clone(), vec![]);
+
+        if let (Some(ns), Some(prefix)) = (self.ns.as_ref(), self.prefix.as_ref()) {
+            elem.prefixes.insert(ns.clone(), prefix.clone());
+        }
+
+        for ((name, ns), value) in &self.attributes {
+            elem.set_attribute(name.clone(), ns.clone(), value.clone());
+        }
+
+        if let Some(ref value) = self.value {
+            elem.text(value.clone());
+        }
+
+        for child in &self.children {
+            elem.tag(child.to_xml());
+        }
+
+        elem
+    }
+
+    pub fn from_xml(elem: &Element) -> ExtensionElement {
+        let text: String = elem.children.iter()
+            .filter_map(|node| match *node {
+                Xml::CharacterNode(ref text) | Xml::CDATANode(ref text) => Some(&text[..]),
+                _ => None,
+            })
+            .collect();
+
+        let children = elem.children.iter()
+            .filter_map(|node| match *node {
+                Xml::ElementNode(ref child) => Some(ExtensionElement::from_xml(child)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // Whitespace between child elements is formatting rather than a value
+        let value = if children.is_empty() || !text.trim().is_empty() { Some(text) } else { None };
+
+        ExtensionElement {
+            name: elem.name.clone(),
+            ns: elem.ns.clone(),
+            prefix: elem.ns.as_ref().and_then(|ns| elem.prefixes.get(ns)).cloned(),
+            attributes: elem.attributes.iter()
+                .filter(|&((name, ns), _)| match *ns {
+                    Some(ref ns) => ns != XMLNS_NS,
+                    None => name != "xmlns",
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            value: value.filter(|text| !text.is_empty()),
+            children,
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! [OpenSearch 1.1](https://github.com/dewitt/opensearch) response elements, and description
+//! documents.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom"
+//!       xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>Search results for "rust"</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <opensearch:totalResults>4230000</opensearch:totalResults>
+//!   <opensearch:startIndex>21</opensearch:startIndex>
+//!   <opensearch:itemsPerPage>10</opensearch:itemsPerPage>
+//!   <opensearch:Query role="request" searchTerms="rust" startPage="3"/>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//! let opensearch = feed.opensearch.unwrap();
+//!
+//! assert_eq!(opensearch.total_results, Some(4230000));
+//! assert_eq!(opensearch.queries[0].start_page, Some(3));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+use xml::Element;
+
+use ::{ElementUtils, ViaXml, ViaXmlChildren, WriteOptions};
+use super::{attribute, child_text, element, element_with_text, from_children};
+use ::writer;
+
+
+/// The OpenSearch 1.1 namespace
+pub const NAMESPACE: &str = "http://a9.com/-/spec/opensearch/1.1/";
+const PREFIX: &str = "opensearch";
+
+
+/// The OpenSearch elements of a page of search results.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpenSearchExtension {
+    pub total_results: Option<u64>,
+    /// The index of the first result on this page
+    pub start_index: Option<u64>,
+    pub items_per_page: Option<u64>,
+    pub queries: Vec<OpenSearchQuery>,
+}
+
+impl ViaXmlChildren for OpenSearchExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        append_optional_number(parent, "totalResults", self.total_results);
+        append_optional_number(parent, "startIndex", self.start_index);
+        append_optional_number(parent, "itemsPerPage", self.items_per_page);
+
+        for query in &self.queries {
+            parent.tag(query.to_xml());
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = OpenSearchExtension {
+            total_results: child_number(parent, "totalResults"),
+            start_index: child_number(parent, "startIndex"),
+            items_per_page: child_number(parent, "itemsPerPage"),
+            queries: from_children(parent, "Query", NAMESPACE),
+        };
+
+        if extension == OpenSearchExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// `<opensearch:Query>`, describing a search that was or could be performed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpenSearchQuery {
+    /// `request`, `example`, `related`, `correction`, `subset` or `superset`
+    pub role: String,
+    pub title: Option<String>,
+    pub total_results: Option<u64>,
+    pub search_terms: Option<String>,
+    pub count: Option<u64>,
+    pub start_index: Option<u64>,
+    pub start_page: Option<u64>,
+    pub language: Option<String>,
+    pub input_encoding: Option<String>,
+    pub output_encoding: Option<String>,
+}
+
+impl ViaXml for OpenSearchQuery {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("Query", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("role", &self.role);
+        elem.attribute_with_optional_text("title", &self.title);
+        elem.attribute_with_optional_text("totalResults", &self.total_results.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("searchTerms", &self.search_terms);
+        elem.attribute_with_optional_text("count", &self.count.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("startIndex", &self.start_index.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("startPage", &self.start_page.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("language", &self.language);
+        elem.attribute_with_optional_text("inputEncoding", &self.input_encoding);
+        elem.attribute_with_optional_text("outputEncoding", &self.output_encoding);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let role = match elem.get_attribute("role", None) {
+            Some(role) => role.to_string(),
+            None => return Err(r#"<opensearch:Query> is missing required "role" attribute"#),
+        };
+
+        Ok(OpenSearchQuery {
+            role,
+            title: elem.get_attribute("title", None).map(String::from),
+            total_results: attribute(&elem, "totalResults"),
+            search_terms: elem.get_attribute("searchTerms", None).map(String::from),
+            count: attribute(&elem, "count"),
+            start_index: attribute(&elem, "startIndex"),
+            start_page: attribute(&elem, "startPage"),
+            language: elem.get_attribute("language", None).map(String::from),
+            input_encoding: elem.get_attribute("inputEncoding", None).map(String::from),
+            output_encoding: elem.get_attribute("outputEncoding", None).map(String::from),
+        })
+    }
+}
+
+
+/// An OpenSearch description document, telling clients how to query a search engine.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::extension::opensearch::{OpenSearchDescription, OpenSearchUrl};
+///
+/// let description = OpenSearchDescription {
+///     short_name: String::from("Blog Search"),
+///     description: String::from("Search the posts on test.blog"),
+///     urls: vec![
+///         OpenSearchUrl {
+///             template: String::from("https://test.blog/search?q={searchTerms}&p={startPage?}"),
+///             mediatype: String::from("application/atom+xml"),
+///             ..Default::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let xml = description.to_string();
+/// let description = xml.parse::<OpenSearchDescription>().unwrap();
+/// assert_eq!(description.short_name, "Blog Search");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpenSearchDescription {
+    pub short_name: String,
+    pub description: String,
+    pub urls: Vec<OpenSearchUrl>,
+    pub contact: Option<String>,
+    /// Space separated keywords
+    pub tags: Option<String>,
+    pub long_name: Option<String>,
+    pub images: Vec<OpenSearchImage>,
+    pub queries: Vec<OpenSearchQuery>,
+    pub developer: Option<String>,
+    pub attribution: Option<String>,
+    /// `open`, `limited`, `private` or `closed`
+    pub syndication_right: Option<String>,
+    pub adult_content: Option<bool>,
+    pub languages: Vec<String>,
+    pub input_encodings: Vec<String>,
+    pub output_encodings: Vec<String>,
+}
+
+impl OpenSearchDescription {
+    /// Writes the description document using the given options.
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        writer::write(&self.to_xml(), options)
+    }
+}
+
+impl ViaXml for OpenSearchDescription {
+    fn to_xml(&self) -> Element {
+        let mut root = element("OpenSearchDescription", NAMESPACE, PREFIX);
+
+        append_text(&mut root, "ShortName", &self.short_name);
+        append_text(&mut root, "Description", &self.description);
+
+        for url in &self.urls {
+            root.tag(url.to_xml());
+        }
+
+        append_optional_text(&mut root, "Contact", &self.contact);
+        append_optional_text(&mut root, "Tags", &self.tags);
+        append_optional_text(&mut root, "LongName", &self.long_name);
+
+        for image in &self.images {
+            root.tag(image.to_xml());
+        }
+
+        for query in &self.queries {
+            root.tag(query.to_xml());
+        }
+
+        append_optional_text(&mut root, "Developer", &self.developer);
+        append_optional_text(&mut root, "Attribution", &self.attribution);
+        append_optional_text(&mut root, "SyndicationRight", &self.syndication_right);
+        append_optional_text(&mut root, "AdultContent", &self.adult_content.map(|b| b.to_string()));
+
+        let lists = [("Language", &self.languages), ("InputEncoding", &self.input_encodings),
+                     ("OutputEncoding", &self.output_encodings)];
+
+        for &(name, values) in lists.iter() {
+            for value in values {
+                append_text(&mut root, name, value);
+            }
+        }
+
+        root
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let short_name = match child_text(&elem, "ShortName", NAMESPACE) {
+            Some(short_name) => short_name,
+            None => return Err("<OpenSearchDescription> is missing required <ShortName> element"),
+        };
+
+        let description = match child_text(&elem, "Description", NAMESPACE) {
+            Some(description) => description,
+            None => return Err("<OpenSearchDescription> is missing required <Description> element"),
+        };
+
+        let urls: Vec<OpenSearchUrl> = from_children(&elem, "Url", NAMESPACE);
+
+        if urls.is_empty() {
+            return Err("<OpenSearchDescription> is missing required <Url> element");
+        }
+
+        let all = |name| -> Vec<String> {
+            elem.get_children(name, Some(NAMESPACE))
+                .map(|e| e.content_str().trim().to_string())
+                .collect()
+        };
+
+        // Anything other than these values means the content is for adults
+        let adult_content = child_text(&elem, "AdultContent", NAMESPACE)
+            .map(|s| !["false", "FALSE", "0", "no", "NO"].contains(&&s[..]));
+
+        Ok(OpenSearchDescription {
+            short_name,
+            description,
+            urls,
+            contact: child_text(&elem, "Contact", NAMESPACE),
+            tags: child_text(&elem, "Tags", NAMESPACE),
+            long_name: child_text(&elem, "LongName", NAMESPACE),
+            images: from_children(&elem, "Image", NAMESPACE),
+            queries: from_children(&elem, "Query", NAMESPACE),
+            developer: child_text(&elem, "Developer", NAMESPACE),
+            attribution: child_text(&elem, "Attribution", NAMESPACE),
+            syndication_right: child_text(&elem, "SyndicationRight", NAMESPACE),
+            adult_content,
+            languages: all("Language"),
+            input_encodings: all("InputEncoding"),
+            output_encodings: all("OutputEncoding"),
+        })
+    }
+}
+
+impl FromStr for OpenSearchDescription {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let root = ::read_root(s)?;
+
+        if root.name != "OpenSearchDescription" {
+            return Err("Document root is not an <OpenSearchDescription> element");
+        }
+
+        if root.ns.as_ref().map(|ns| &ns[..]) != Some(NAMESPACE) {
+            return Err("<OpenSearchDescription> is not in the OpenSearch 1.1 namespace");
+        }
+
+        OpenSearchDescription::from_xml(root)
+    }
+}
+
+impl fmt::Display for OpenSearchDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
+    }
+}
+
+
+/// `<Url>`, a template for the URL of one kind of search results.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpenSearchUrl {
+    /// The URL with `{searchTerms}`-style parameters to substitute
+    pub template: String,
+    /// The MIME type of the results
+    pub mediatype: String,
+    /// `results`, `suggestions`, `self` or `collection`
+    pub rel: Option<String>,
+    /// The index of the first result, 1 when absent
+    pub index_offset: Option<i64>,
+    /// The number of the first page, 1 when absent
+    pub page_offset: Option<i64>,
+}
+
+impl ViaXml for OpenSearchUrl {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("Url", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("template", &self.template);
+        elem.attribute_with_text("type", &self.mediatype);
+        elem.attribute_with_optional_text("rel", &self.rel);
+        elem.attribute_with_optional_text("indexOffset", &self.index_offset.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("pageOffset", &self.page_offset.map(|n| n.to_string()));
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let template = match elem.get_attribute("template", None) {
+            Some(template) => template.to_string(),
+            None => return Err(r#"<Url> is missing required "template" attribute"#),
+        };
+
+        let mediatype = match elem.get_attribute("type", None) {
+            Some(mediatype) => mediatype.to_string(),
+            None => return Err(r#"<Url> is missing required "type" attribute"#),
+        };
+
+        Ok(OpenSearchUrl {
+            template,
+            mediatype,
+            rel: elem.get_attribute("rel", None).map(String::from),
+            index_offset: attribute(&elem, "indexOffset"),
+            page_offset: attribute(&elem, "pageOffset"),
+        })
+    }
+}
+
+
+/// `<Image>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OpenSearchImage {
+    pub url: String,
+    pub height: Option<u32>,
+    pub width: Option<u32>,
+    pub mediatype: Option<String>,
+}
+
+impl ViaXml for OpenSearchImage {
+    fn to_xml(&self) -> Element {
+        let mut elem = element_with_text("Image", NAMESPACE, PREFIX, &self.url);
+
+        elem.attribute_with_optional_text("height", &self.height.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("width", &self.width.map(|n| n.to_string()));
+        elem.attribute_with_optional_text("type", &self.mediatype);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Ok(OpenSearchImage {
+            url: elem.content_str().trim().to_string(),
+            height: attribute(&elem, "height"),
+            width: attribute(&elem, "width"),
+            mediatype: elem.get_attribute("type", None).map(String::from),
+        })
+    }
+}
+
+
+fn append_text(parent: &mut Element, name: &str, text: &str) {
+    parent.tag(element_with_text(name, NAMESPACE, PREFIX, text));
+}
+
+
+fn append_optional_text(parent: &mut Element, name: &str, text: &Option<String>) {
+    if let Some(ref text) = *text {
+        append_text(parent, name, text);
+    }
+}
+
+
+fn append_optional_number(parent: &mut Element, name: &str, number: Option<u64>) {
+    append_optional_text(parent, name, &number.map(|n| n.to_string()));
+}
+
+
+fn child_number(parent: &Element, name: &str) -> Option<u64> {
+    child_text(parent, name, NAMESPACE).and_then(|s| s.parse().ok())
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{OpenSearchDescription, OpenSearchExtension, OpenSearchImage, OpenSearchQuery,
+                OpenSearchUrl};
+    use ::Feed;
+
+    #[test]
+    fn test_round_trip() {
+        let opensearch = OpenSearchExtension {
+            total_results: Some(4230000),
+            start_index: Some(21),
+            items_per_page: Some(10),
+            queries: vec![
+                OpenSearchQuery {
+                    role: "request".to_string(),
+                    search_terms: Some("rust & xml".to_string()),
+                    start_page: Some(3),
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let feed = Feed { opensearch: Some(opensearch.clone()), ..Default::default() };
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("<opensearch:totalResults>4230000</opensearch:totalResults>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.opensearch, Some(opensearch));
+    }
+
+    #[test]
+    fn test_read_description() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/">
+                <ShortName>Web Search</ShortName>
+                <Description>Use Example.com to search the Web.</Description>
+                <Tags>example web</Tags>
+                <Contact>admin@example.com</Contact>
+                <Url type="application/atom+xml" template="http://example.com/?q={searchTerms}&amp;pw={startPage?}&amp;format=atom"/>
+                <Url type="text/html" template="http://example.com/?q={searchTerms}" indexOffset="0"/>
+                <Url template="http://example.com/broken"/>
+                <Image height="16" width="16" type="image/vnd.microsoft.icon">http://example.com/websearch.ico</Image>
+                <Query role="example" searchTerms="cat"/>
+                <AdultContent>false</AdultContent>
+                <Language>en-us</Language>
+                <Language>fr</Language>
+                <OutputEncoding>UTF-8</OutputEncoding>
+            </OpenSearchDescription>"#;
+
+        let description = OpenSearchDescription::from_str(xml).unwrap();
+        assert_eq!(description.short_name, "Web Search");
+        assert_eq!(description.urls.len(), 2);
+        assert_eq!(description.urls[1], OpenSearchUrl {
+            template: "http://example.com/?q={searchTerms}".to_string(),
+            mediatype: "text/html".to_string(),
+            index_offset: Some(0),
+            ..Default::default()
+        });
+        assert_eq!(description.images[0], OpenSearchImage {
+            url: "http://example.com/websearch.ico".to_string(),
+            height: Some(16),
+            width: Some(16),
+            mediatype: Some("image/vnd.microsoft.icon".to_string()),
+        });
+        assert_eq!(description.queries[0].search_terms.as_ref().unwrap(), "cat");
+        assert_eq!(description.adult_content, Some(false));
+        assert_eq!(description.languages, vec!["en-us", "fr"]);
+
+        let reread = OpenSearchDescription::from_str(&description.to_string()).unwrap();
+        assert_eq!(reread, description);
+        assert!(description.to_string().contains(r#"<OpenSearchDescription xmlns='http://a9.com/-/spec/opensearch/1.1/'>"#));
+    }
+
+    #[test]
+    fn test_read_invalid_description() {
+        let missing_url = r#"
+            <OpenSearchDescription xmlns="http://a9.com/-/spec/opensearch/1.1/">
+                <ShortName>Web Search</ShortName>
+                <Description>Use Example.com to search the Web.</Description>
+            </OpenSearchDescription>"#;
+        assert_eq!(OpenSearchDescription::from_str(missing_url).unwrap_err(),
+                   "<OpenSearchDescription> is missing required <Url> element");
+
+        let wrong_namespace = "<OpenSearchDescription><ShortName>Web Search</ShortName></OpenSearchDescription>";
+        assert_eq!(OpenSearchDescription::from_str(wrong_namespace).unwrap_err(),
+                   "<OpenSearchDescription> is not in the OpenSearch 1.1 namespace");
+    }
+}
@@ -0,0 +1,518 @@
+//! [Podcasting 2.0](https://github.com/Podcastindex-org/podcast-namespace) elements.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom" xmlns:podcast="https://podcastindex.org/namespace/1.0">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>My Podcast</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <podcast:locked owner="n@test.blog">yes</podcast:locked>
+//!   <entry>
+//!     <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+//!     <title>Episode 1</title>
+//!     <updated>2019-04-01T07:30:00Z</updated>
+//!     <podcast:transcript url="http://test.blog/1.vtt" type="text/vtt"/>
+//!   </entry>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//!
+//! let locked = feed.podcast.unwrap().locked.unwrap();
+//! assert!(locked.locked);
+//!
+//! let episode = feed.entries[0].podcast.as_ref().unwrap();
+//! assert_eq!(episode.transcripts[0].mediatype, "text/vtt");
+//! ```
+
+use xml::Element;
+
+use ::{ElementUtils, ViaXml, ViaXmlChildren};
+use super::{attribute, child_text, element, element_with_text, from_children};
+
+
+/// The Podcasting 2.0 namespace
+pub const NAMESPACE: &str = "https://podcastindex.org/namespace/1.0";
+const PREFIX: &str = "podcast";
+
+
+/// The Podcasting 2.0 elements describing a whole podcast.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastFeedExtension {
+    /// A globally unique identifier for the podcast, normally a UUIDv5 of its feed URL
+    pub guid: Option<String>,
+    pub locked: Option<PodcastLocked>,
+    pub funding: Vec<PodcastFunding>,
+    pub persons: Vec<PodcastPerson>,
+    pub value: Option<PodcastValue>,
+}
+
+impl ViaXmlChildren for PodcastFeedExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        if let Some(ref guid) = self.guid {
+            parent.tag(element_with_text("guid", NAMESPACE, PREFIX, guid));
+        }
+
+        if let Some(ref locked) = self.locked {
+            parent.tag(locked.to_xml());
+        }
+
+        for funding in &self.funding {
+            parent.tag(funding.to_xml());
+        }
+
+        for person in &self.persons {
+            parent.tag(person.to_xml());
+        }
+
+        if let Some(ref value) = self.value {
+            parent.tag(value.to_xml());
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = PodcastFeedExtension {
+            guid: child_text(parent, "guid", NAMESPACE),
+            locked: from_children(parent, "locked", NAMESPACE).into_iter().next(),
+            funding: from_children(parent, "funding", NAMESPACE),
+            persons: from_children(parent, "person", NAMESPACE),
+            value: from_children(parent, "value", NAMESPACE).into_iter().next(),
+        };
+
+        if extension == PodcastFeedExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// The Podcasting 2.0 elements describing a single episode.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastEntryExtension {
+    pub transcripts: Vec<PodcastTranscript>,
+    pub chapters: Option<PodcastChapters>,
+    pub persons: Vec<PodcastPerson>,
+    pub season: Option<PodcastSeason>,
+    pub value: Option<PodcastValue>,
+}
+
+impl ViaXmlChildren for PodcastEntryExtension {
+    fn append_xml(&self, parent: &mut Element) {
+        for transcript in &self.transcripts {
+            parent.tag(transcript.to_xml());
+        }
+
+        if let Some(ref chapters) = self.chapters {
+            parent.tag(chapters.to_xml());
+        }
+
+        for person in &self.persons {
+            parent.tag(person.to_xml());
+        }
+
+        if let Some(ref season) = self.season {
+            parent.tag(season.to_xml());
+        }
+
+        if let Some(ref value) = self.value {
+            parent.tag(value.to_xml());
+        }
+    }
+
+    fn from_xml_children(parent: &Element) -> Option<Self> {
+        let extension = PodcastEntryExtension {
+            transcripts: from_children(parent, "transcript", NAMESPACE),
+            chapters: from_children(parent, "chapters", NAMESPACE).into_iter().next(),
+            persons: from_children(parent, "person", NAMESPACE),
+            season: from_children(parent, "season", NAMESPACE).into_iter().next(),
+            value: from_children(parent, "value", NAMESPACE).into_iter().next(),
+        };
+
+        if extension == PodcastEntryExtension::default() {
+            None
+        } else {
+            Some(extension)
+        }
+    }
+}
+
+
+/// `<podcast:locked>`, asking other platforms not to import the podcast.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastLocked {
+    pub locked: bool,
+    /// The email address that can unlock the podcast
+    pub owner: Option<String>,
+}
+
+impl ViaXml for PodcastLocked {
+    fn to_xml(&self) -> Element {
+        let value = if self.locked { "yes" } else { "no" };
+        let mut elem = element_with_text("locked", NAMESPACE, PREFIX, value);
+        elem.attribute_with_optional_text("owner", &self.owner);
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let locked = match &elem.content_str().trim().to_lowercase()[..] {
+            "yes" | "true" => true,
+            "no" | "false" => false,
+            _ => return Err(r#"<podcast:locked> must contain "yes" or "no""#),
+        };
+
+        Ok(PodcastLocked {
+            locked,
+            owner: elem.get_attribute("owner", None).map(String::from),
+        })
+    }
+}
+
+
+/// `<podcast:funding>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastFunding {
+    pub url: String,
+    /// A short call to action for the link
+    pub value: String,
+}
+
+impl ViaXml for PodcastFunding {
+    fn to_xml(&self) -> Element {
+        let mut elem = element_with_text("funding", NAMESPACE, PREFIX, &self.value);
+        elem.attribute_with_text("url", &self.url);
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let url = match elem.get_attribute("url", None) {
+            Some(url) => url.to_string(),
+            None => return Err(r#"<podcast:funding> is missing required "url" attribute"#),
+        };
+
+        Ok(PodcastFunding {
+            url,
+            value: elem.content_str().trim().to_string(),
+        })
+    }
+}
+
+
+/// `<podcast:person>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastPerson {
+    pub name: String,
+    /// A role from the Podcast Taxonomy Project, `host` when absent
+    pub role: Option<String>,
+    /// A group from the Podcast Taxonomy Project, `cast` when absent
+    pub group: Option<String>,
+    /// The URL of a picture of the person
+    pub img: Option<String>,
+    pub href: Option<String>,
+}
+
+impl ViaXml for PodcastPerson {
+    fn to_xml(&self) -> Element {
+        let mut elem = element_with_text("person", NAMESPACE, PREFIX, &self.name);
+
+        elem.attribute_with_optional_text("role", &self.role);
+        elem.attribute_with_optional_text("group", &self.group);
+        elem.attribute_with_optional_text("img", &self.img);
+        elem.attribute_with_optional_text("href", &self.href);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Ok(PodcastPerson {
+            name: elem.content_str().trim().to_string(),
+            role: elem.get_attribute("role", None).map(String::from),
+            group: elem.get_attribute("group", None).map(String::from),
+            img: elem.get_attribute("img", None).map(String::from),
+            href: elem.get_attribute("href", None).map(String::from),
+        })
+    }
+}
+
+
+/// `<podcast:transcript>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastTranscript {
+    pub url: String,
+    /// The MIME type of the transcript, such as `text/vtt` or `application/srt`
+    pub mediatype: String,
+    pub language: Option<String>,
+    /// `captions` when the transcript is meant to be shown as closed captions
+    pub rel: Option<String>,
+}
+
+impl ViaXml for PodcastTranscript {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("transcript", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("url", &self.url);
+        elem.attribute_with_text("type", &self.mediatype);
+        elem.attribute_with_optional_text("language", &self.language);
+        elem.attribute_with_optional_text("rel", &self.rel);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let url = match elem.get_attribute("url", None) {
+            Some(url) => url.to_string(),
+            None => return Err(r#"<podcast:transcript> is missing required "url" attribute"#),
+        };
+
+        let mediatype = match elem.get_attribute("type", None) {
+            Some(mediatype) => mediatype.to_string(),
+            None => return Err(r#"<podcast:transcript> is missing required "type" attribute"#),
+        };
+
+        Ok(PodcastTranscript {
+            url,
+            mediatype,
+            language: elem.get_attribute("language", None).map(String::from),
+            rel: elem.get_attribute("rel", None).map(String::from),
+        })
+    }
+}
+
+
+/// `<podcast:chapters>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastChapters {
+    pub url: String,
+    /// The MIME type of the chapters file, normally `application/json+chapters`
+    pub mediatype: String,
+}
+
+impl ViaXml for PodcastChapters {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("chapters", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("url", &self.url);
+        elem.attribute_with_text("type", &self.mediatype);
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let url = match elem.get_attribute("url", None) {
+            Some(url) => url.to_string(),
+            None => return Err(r#"<podcast:chapters> is missing required "url" attribute"#),
+        };
+
+        let mediatype = match elem.get_attribute("type", None) {
+            Some(mediatype) => mediatype.to_string(),
+            None => return Err(r#"<podcast:chapters> is missing required "type" attribute"#),
+        };
+
+        Ok(PodcastChapters {
+            url,
+            mediatype,
+        })
+    }
+}
+
+
+/// `<podcast:season>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastSeason {
+    pub number: u32,
+    pub name: Option<String>,
+}
+
+impl ViaXml for PodcastSeason {
+    fn to_xml(&self) -> Element {
+        let mut elem = element_with_text("season", NAMESPACE, PREFIX, &self.number.to_string());
+        elem.attribute_with_optional_text("name", &self.name);
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let number = match elem.content_str().trim().parse() {
+            Ok(number) => number,
+            Err(_) => return Err("<podcast:season> must contain a whole number"),
+        };
+
+        Ok(PodcastSeason {
+            number,
+            name: elem.get_attribute("name", None).map(String::from),
+        })
+    }
+}
+
+
+/// `<podcast:value>`, describing how listeners can pay the podcast while listening.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastValue {
+    /// The payment layer, such as `lightning`
+    pub value_type: String,
+    /// The payment method, such as `keysend`
+    pub method: String,
+    /// The suggested amount per minute of listening
+    pub suggested: Option<String>,
+    pub recipients: Vec<PodcastValueRecipient>,
+}
+
+impl ViaXml for PodcastValue {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("value", NAMESPACE, PREFIX);
+
+        elem.attribute_with_text("type", &self.value_type);
+        elem.attribute_with_text("method", &self.method);
+        elem.attribute_with_optional_text("suggested", &self.suggested);
+
+        for recipient in &self.recipients {
+            elem.tag(recipient.to_xml());
+        }
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let value_type = match elem.get_attribute("type", None) {
+            Some(value_type) => value_type.to_string(),
+            None => return Err(r#"<podcast:value> is missing required "type" attribute"#),
+        };
+
+        let method = match elem.get_attribute("method", None) {
+            Some(method) => method.to_string(),
+            None => return Err(r#"<podcast:value> is missing required "method" attribute"#),
+        };
+
+        Ok(PodcastValue {
+            value_type,
+            method,
+            suggested: elem.get_attribute("suggested", None).map(String::from),
+            recipients: from_children(&elem, "valueRecipient", NAMESPACE),
+        })
+    }
+}
+
+
+/// `<podcast:valueRecipient>`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PodcastValueRecipient {
+    pub name: Option<String>,
+    pub custom_key: Option<String>,
+    pub custom_value: Option<String>,
+    /// The kind of address, such as `node`
+    pub recipient_type: String,
+    pub address: String,
+    /// This recipient's share of each payment, relative to the other recipients
+    pub split: u32,
+    /// Whether the share is a fee taken from the total rather than a split of it
+    pub fee: Option<bool>,
+}
+
+impl ViaXml for PodcastValueRecipient {
+    fn to_xml(&self) -> Element {
+        let mut elem = element("valueRecipient", NAMESPACE, PREFIX);
+
+        elem.attribute_with_optional_text("name", &self.name);
+        elem.attribute_with_optional_text("customKey", &self.custom_key);
+        elem.attribute_with_optional_text("customValue", &self.custom_value);
+        elem.attribute_with_text("type", &self.recipient_type);
+        elem.attribute_with_text("address", &self.address);
+        elem.attribute_with_text("split", &self.split.to_string());
+        elem.attribute_with_optional_text("fee", &self.fee.map(|b| b.to_string()));
+
+        elem
+    }
+
+    fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        let recipient_type = match elem.get_attribute("type", None) {
+            Some(recipient_type) => recipient_type.to_string(),
+            None => return Err(r#"<podcast:valueRecipient> is missing required "type" attribute"#),
+        };
+
+        let address = match elem.get_attribute("address", None) {
+            Some(address) => address.to_string(),
+            None => return Err(r#"<podcast:valueRecipient> is missing required "address" attribute"#),
+        };
+
+        let split = match attribute(&elem, "split") {
+            Some(split) => split,
+            None => return Err(r#"<podcast:valueRecipient> is missing a valid "split" attribute"#),
+        };
+
+        Ok(PodcastValueRecipient {
+            name: elem.get_attribute("name", None).map(String::from),
+            custom_key: elem.get_attribute("customKey", None).map(String::from),
+            custom_value: elem.get_attribute("customValue", None).map(String::from),
+            recipient_type,
+            address,
+            split,
+            fee: attribute(&elem, "fee"),
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Read;
+    use std::str::FromStr;
+
+    use super::{PodcastChapters, PodcastLocked, PodcastSeason, PodcastValueRecipient};
+    use ::Feed;
+
+    fn read_sample() -> Feed {
+        let mut file = File::open("test-data/podcast.xml").unwrap();
+        let mut atom_string = String::new();
+        file.read_to_string(&mut atom_string).unwrap();
+        Feed::from_str(&atom_string).unwrap()
+    }
+
+    #[test]
+    fn test_read_sample() {
+        let feed = read_sample();
+
+        let podcast = feed.podcast.as_ref().unwrap();
+        assert_eq!(podcast.guid.as_ref().unwrap(), "917393e3-1b1e-5cef-ace4-edaa54e1f810");
+        assert_eq!(podcast.locked, Some(PodcastLocked { locked: true, owner: Some("n@test.blog".to_string()) }));
+        assert_eq!(podcast.funding[0].url, "https://test.blog/donate");
+        assert_eq!(podcast.funding[0].value, "Support the show!");
+        assert_eq!(podcast.persons[0].name, "N. Blogger");
+
+        let value = podcast.value.as_ref().unwrap();
+        assert_eq!(value.value_type, "lightning");
+        assert_eq!(value.recipients.len(), 2);
+        assert_eq!(value.recipients[1], PodcastValueRecipient {
+            name: Some("Hosting".to_string()),
+            recipient_type: "node".to_string(),
+            address: "03ae9f91a0cb8ff43840e3c322c4c61f019d8c1c3cea15a25cfc425ac605e61a4a".to_string(),
+            split: 1,
+            fee: Some(true),
+            ..Default::default()
+        });
+
+        let episode = feed.entries[0].podcast.as_ref().unwrap();
+        assert_eq!(episode.transcripts.len(), 2);
+        assert_eq!(episode.transcripts[1].rel.as_ref().unwrap(), "captions");
+        assert_eq!(episode.chapters, Some(PodcastChapters {
+            url: "https://test.blog/1/chapters.json".to_string(),
+            mediatype: "application/json+chapters".to_string(),
+        }));
+        assert_eq!(episode.persons[0].role.as_ref().unwrap(), "guest");
+        assert_eq!(episode.season, Some(PodcastSeason { number: 2, name: Some("Round Two".to_string()) }));
+        assert!(episode.value.is_none());
+    }
+
+    #[test]
+    fn test_round_trip_sample() {
+        let feed = read_sample();
+        let reread = Feed::from_str(&feed.to_string()).unwrap();
+
+        assert_eq!(reread.podcast, feed.podcast);
+        assert_eq!(reread.entries[0].podcast, feed.entries[0].podcast);
+    }
+}
@@ -1,7 +1,21 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
 use std::str::FromStr;
-use xml::{Element, ElementBuilder, Parser, Xml};
+use encoding_rs::Encoding;
+use xml::Element;
 
-use ::{Author, Category, Contributor, ElementUtils, Entry, Generator, Link, NS, Person, ViaXml};
+use ::{Author, Category, CategoryIndex, Contributor, ElementUtils, Enclosure, Entry, FeedDiff, FromScopedXml, Generator, IndexOptions, Link, LinkPreferences, LinkRel, NS, Person, ResolveUris, Text, ViaXml, ViaXmlChildren, WriteOptions, XmlScope};
+use ::encoding;
+use ::extension::dublincore::{self, DublinCoreExtension};
+use ::extension::georss::{BoundingBox, GeoRssExtension};
+use ::extension::itunes::ITunesFeedExtension;
+use ::extension::media::MediaExtension;
+use ::extension::opensearch::OpenSearchExtension;
+use ::extension::podcast::PodcastFeedExtension;
+use ::link;
+use ::text;
+use ::writer;
 
 
 /// [The Atom Syndication Format § The "atom:feed" Element]
@@ -14,41 +28,306 @@ use ::{Author, Category, Contributor, ElementUtils, Entry, Generator, Link, NS,
 ///
 /// let feed = Feed {
 ///     id: String::from("6011425f-414d-4a17-84ba-b731c2bb1fc2"),
-///     title: String::from("My Blog"),
+///     title: "My Blog".into(),
 ///     updated: String::from("2015-05-11T21:30:54Z"),
 ///     entries: vec![],
 ///     ..Default::default()
 /// };
 /// ```
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Feed {
     pub id: String,
-    pub title: String,
+    pub title: Text,
     pub updated: String,
     pub icon: Option<String>,
     pub logo: Option<String>,
-    pub rights: Option<String>,
-    pub subtitle: Option<String>,
+    pub rights: Option<Text>,
+    pub subtitle: Option<Text>,
     pub generator: Option<Generator>,
     pub links: Vec<Link>,
     pub categories: Vec<Category>,
     pub authors: Vec<Person>,
     pub contributors: Vec<Person>,
     pub entries: Vec<Entry>,
+    pub media: Option<MediaExtension>,
+    pub podcast: Option<PodcastFeedExtension>,
+    pub itunes: Option<ITunesFeedExtension>,
+    pub dublin_core: Option<DublinCoreExtension>,
+    pub georss: Option<GeoRssExtension>,
+    pub opensearch: Option<OpenSearchExtension>,
+    /// The effective `xml:base` of the feed, which IRIs inside it may be relative to
+    pub base: Option<String>,
+    /// The effective `xml:lang` of the feed
+    pub lang: Option<String>,
+}
+
+
+impl Feed {
+    /// Makes every IRI in the feed absolute, resolving them against their effective `xml:base`
+    /// and finally against `document_url`, the URL the feed was retrieved from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::Feed;
+    ///
+    /// let atom_str = r#"
+    /// <feed xmlns="http://www.w3.org/2005/Atom" xml:base="/blog/">
+    ///   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+    ///   <title>My Blog</title>
+    ///   <updated>2019-04-01T07:30:00Z</updated>
+    ///   <link href="posts/42"/>
+    /// </feed>
+    /// "#;
+    ///
+    /// let mut feed = atom_str.parse::<Feed>().unwrap();
+    /// assert_eq!(feed.links[0].href, "posts/42");
+    ///
+    /// feed.resolve_relative_uris("http://example.com/feed.atom");
+    /// assert_eq!(feed.links[0].href, "http://example.com/blog/posts/42");
+    /// ```
+    pub fn resolve_relative_uris(&mut self, document_url: &str) {
+        self.resolve_uris(document_url, document_url);
+    }
+
+    /// The feed's authors, or if it has none, people named by its `dc:creator` elements.
+    pub fn authors_or_creators(&self) -> Cow<'_, [Person]> {
+        dublincore::authors_or_creators(&self.authors, self.dublin_core.as_ref())
+    }
+
+    /// The authors of `entry`, which should be one of this feed's entries.
+    ///
+    /// An entry with no authors inherits those of its `source`, or failing that those of the
+    /// feed, as [RFC 4287 § 4.2.1](https://tools.ietf.org/html/rfc4287#section-4.2.1) describes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Entry, Feed, Person};
+    ///
+    /// let feed = Feed {
+    ///     authors: vec![Person { name: String::from("N. Blogger"), ..Default::default() }],
+    ///     entries: vec![Entry::default()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(feed.effective_authors(&feed.entries[0])[0].name, "N. Blogger");
+    /// ```
+    pub fn effective_authors<'a>(&'a self, entry: &'a Entry) -> &'a [Person] {
+        let source = entry.source.as_ref().map(|source| &source.authors[..]).unwrap_or(&[]);
+
+        [&entry.authors[..], source].iter().cloned()
+            .find(|authors| !authors.is_empty())
+            .unwrap_or(&self.authors)
+    }
+
+    /// The rights of `entry`, which should be one of this feed's entries.
+    ///
+    /// An entry with no rights inherits those of the feed, as
+    /// [RFC 4287 § 4.2.10](https://tools.ietf.org/html/rfc4287#section-4.2.10) describes. The
+    /// rights of a `source` are those of the feed the entry was copied from, so they aren't used.
+    pub fn effective_rights<'a>(&'a self, entry: &'a Entry) -> Option<&'a Text> {
+        entry.rights.as_ref().or(self.rights.as_ref())
+    }
+
+    /// Copies inherited authors and rights into every entry that has none, so that each entry
+    /// can be used on its own, such as after being taken out of the feed.
+    pub fn apply_inheritance(&mut self) {
+        let mut entries = ::std::mem::take(&mut self.entries);
+
+        for entry in &mut entries {
+            let authors = self.effective_authors(entry).to_vec();
+            let rights = self.effective_rights(entry).cloned();
+
+            entry.authors = authors;
+            entry.rights = rights;
+        }
+
+        self.entries = entries;
+    }
+
+    /// `updated` if it is a valid date, otherwise `dcterms:modified` if that is.
+    pub fn updated_or_modified(&self) -> Option<&str> {
+        dublincore::updated_or_modified(&self.updated, self.dublin_core.as_ref())
+    }
+
+    /// The best link to the feed's website for `prefs`. Links with no `rel` count as `alternate`.
+    pub fn alternate_link(&self, prefs: &LinkPreferences) -> Option<&Link> {
+        link::alternate(&self.links, prefs)
+    }
+
+    /// The link to the feed itself, which is its canonical URL.
+    pub fn self_link(&self) -> Option<&Link> {
+        self.links.iter().find(|link| link.has_rel(&LinkRel::Self_))
+    }
+
+    /// The enclosures of every entry, in order.
+    pub fn enclosures(&self) -> impl Iterator<Item = Enclosure<'_>> {
+        self.entries.iter().flat_map(Entry::enclosures)
+    }
+
+    /// The links to the WebSub hubs that publish updates to the feed.
+    pub fn hub_links(&self) -> impl Iterator<Item = &Link> {
+        self.links.iter().filter(|link| link.has_rel(&LinkRel::Hub))
+    }
+
+    /// Reads a feed from raw bytes in any encoding, returning it with the encoding it was read as.
+    ///
+    /// The encoding is taken from a byte order mark if there is one, then from `charset`, which
+    /// should be the `charset` parameter of the HTTP `Content-Type` the feed was served with, then
+    /// from the `<?xml encoding="..."?>` declaration. Without any of these it is read as UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::Feed;
+    ///
+    /// let atom_bytes = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>
+    /// <feed xmlns=\"http://www.w3.org/2005/Atom\">
+    ///   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+    ///   <title>Caf\xe9</title>
+    ///   <updated>2019-04-01T07:30:00Z</updated>
+    /// </feed>";
+    ///
+    /// let (feed, encoding) = Feed::from_bytes(atom_bytes, None).unwrap();
+    /// assert_eq!(feed.title, "Caf\u{e9}");
+    /// assert_eq!(encoding.name(), "windows-1252");
+    /// ```
+    pub fn from_bytes(bytes: &[u8], charset: Option<&str>) -> Result<(Feed, &'static Encoding), &'static str> {
+        let (text, encoding) = encoding::decode(bytes, charset);
+        text.parse().map(|feed| (feed, encoding))
+    }
+
+    /// Serializes the feed as an XML document, formatted as described by `options`.
+    ///
+    /// `to_string()` is the same as calling this with the default options.
+    pub fn to_string_with(&self, options: &WriteOptions) -> String {
+        writer::write(&self.to_document(), options)
+    }
+
+    /// Serializes the feed as an XML document in the encoding chosen by `options`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Feed, OutputEncoding, WriteOptions};
+    ///
+    /// let feed = Feed {
+    ///     title: "Caf\u{e9} \u{2603}".into(),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let options = WriteOptions {
+    ///     encoding: OutputEncoding::Iso8859_1,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let atom_bytes = feed.to_bytes_with(&options);
+    /// assert!(atom_bytes.starts_with(b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>"));
+    /// assert!(atom_bytes.ends_with(b"<title>Caf\xe9 &#x2603;</title><updated></updated></feed>"));
+    /// ```
+    pub fn to_bytes_with(&self, options: &WriteOptions) -> Vec<u8> {
+        writer::write_bytes(&self.to_document(), options)
+    }
+
+    /// The root element, with the `xml:` attributes that merely repeat inherited values removed.
+    fn to_document(&self) -> Element {
+        let mut elem = self.to_xml();
+        ::prune_inherited(&mut elem, "base", None);
+        ::prune_inherited(&mut elem, "lang", None);
+        elem
+    }
+
+    /// Groups the feed's entries by their language, falling back to the language of the feed.
+    ///
+    /// Language tags are compared case-insensitively, and appear lowercased in the keys; entries
+    /// with no known language are grouped under `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Entry, Feed};
+    ///
+    /// let feed = Feed {
+    ///     lang: Some(String::from("en")),
+    ///     entries: vec![
+    ///         Entry { id: String::from("1"), ..Default::default() },
+    ///         Entry { id: String::from("2"), lang: Some(String::from("de")), ..Default::default() },
+    ///         Entry { id: String::from("3"), lang: Some(String::from("EN")), ..Default::default() },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let by_lang = feed.entries_by_lang();
+    /// assert_eq!(by_lang[&Some(String::from("en"))].len(), 2);
+    /// assert_eq!(by_lang[&Some(String::from("de"))][0].id, "2");
+    /// ```
+    pub fn entries_by_lang(&self) -> BTreeMap<Option<String>, Vec<&Entry>> {
+        let mut groups: BTreeMap<Option<String>, Vec<&Entry>> = BTreeMap::new();
+
+        for entry in &self.entries {
+            let lang = entry.lang.as_ref().or(self.lang.as_ref()).map(|l| l.to_lowercase());
+            groups.entry(lang).or_default().push(entry);
+        }
+
+        groups
+    }
+
+    /// The entries with a GeoRSS location that overlaps `bounds`.
+    ///
+    /// Lines and polygons are compared by their bounding boxes, so an entry may be included when
+    /// its shape only comes near `bounds`.
+    pub fn entries_intersecting<'a>(&'a self, bounds: &'a BoundingBox) -> impl Iterator<Item = &'a Entry> {
+        self.entries.iter()
+            .filter(move |entry| entry.georss.as_ref().is_some_and(|georss| georss.intersects(bounds)))
+    }
+
+    /// Compares two snapshots of a feed, matching entries by `id`.
+    ///
+    /// An entry in both snapshots is only reported as updated when its `updated` moved forward,
+    /// since publishers only change it for edits they consider significant. Entries without an
+    /// `id` can't be matched, so they are listed apart in `unidentified`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::{Entry, Feed};
+    ///
+    /// let old = Feed::default();
+    /// let new = Feed {
+    ///     entries: vec![Entry { id: String::from("urn:1"), ..Default::default() }],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let diff = Feed::diff(&old, &new);
+    /// assert_eq!(diff.added[0].id, "urn:1");
+    /// assert!(diff.removed.is_empty());
+    /// ```
+    pub fn diff<'a>(old: &'a Feed, new: &'a Feed) -> FeedDiff<'a> {
+        FeedDiff::new(old, new)
+    }
+
+    /// Indexes the categories of the entries by scheme and term; see `CategoryIndex`.
+    pub fn category_index(&self, options: &IndexOptions) -> CategoryIndex<'_> {
+        CategoryIndex::new(self, options)
+    }
 }
 
 impl ViaXml for Feed {
     fn to_xml(&self) -> Element {
         let mut feed = Element::new("feed".to_string(), Some(NS.to_string()), vec![]);
 
+        feed.xml_attribute_with_optional_text("base", &self.base);
+        feed.xml_attribute_with_optional_text("lang", &self.lang);
+
         feed.tag_with_text("id", &self.id);
-        feed.tag_with_text("title", &self.title);
+        feed.tag_with_text_construct("title", &self.title);
         feed.tag_with_text("updated", &self.updated);
 
         feed.tag_with_optional_text("icon", &self.icon);
         feed.tag_with_optional_text("logo", &self.logo);
-        feed.tag_with_optional_text("rights", &self.rights);
-        feed.tag_with_optional_text("subtitle", &self.subtitle);
+        feed.tag_with_optional_text_construct("rights", &self.rights);
+        feed.tag_with_optional_text_construct("subtitle", &self.subtitle);
 
         if let Some(ref g) = self.generator {
             feed.tag(g.to_xml());
@@ -70,6 +349,30 @@ impl ViaXml for Feed {
             feed.tag(Contributor(person.clone()).to_xml());
         }
 
+        if let Some(ref media) = self.media {
+            media.append_xml(&mut feed);
+        }
+
+        if let Some(ref podcast) = self.podcast {
+            podcast.append_xml(&mut feed);
+        }
+
+        if let Some(ref itunes) = self.itunes {
+            itunes.append_xml(&mut feed);
+        }
+
+        if let Some(ref dublin_core) = self.dublin_core {
+            dublin_core.append_xml(&mut feed);
+        }
+
+        if let Some(ref georss) = self.georss {
+            georss.append_xml(&mut feed);
+        }
+
+        if let Some(ref opensearch) = self.opensearch {
+            opensearch.append_xml(&mut feed);
+        }
+
         for entry in &self.entries {
             feed.tag(entry.to_xml());
         }
@@ -78,13 +381,22 @@ impl ViaXml for Feed {
     }
 
     fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Feed::from_scoped_xml(&elem, &XmlScope::default())
+    }
+}
+
+
+impl FromScopedXml for Feed {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str> {
+        let scope = parent.of(elem);
+
         let id = match elem.get_child("id", Some(NS)) {
             Some(elem) => elem.content_str(),
             None => return Err("<feed> is missing required <id> element"),
         };
 
         let title = match elem.get_child("title", Some(NS)) {
-            Some(elem) => elem.content_str(),
+            Some(elem) => text::from_xml(elem, &scope),
             None => return Err("<feed> is missing required <title> element"),
         };
 
@@ -95,76 +407,112 @@ impl ViaXml for Feed {
 
         let icon = elem.get_child("icon", Some(NS)).map(Element::content_str);
         let logo = elem.get_child("logo", Some(NS)).map(Element::content_str);
-        let rights = elem.get_child("rights", Some(NS)).map(Element::content_str);
-        let subtitle = elem.get_child("subtitle", Some(NS)).map(Element::content_str);
-        let generator = elem.get_child("generator", Some(NS)).map(|e| ViaXml::from_xml(e.clone()).unwrap());
+        let rights = elem.get_child("rights", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let subtitle = elem.get_child("subtitle", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let generator = elem.get_child("generator", Some(NS)).map(|e| FromScopedXml::from_scoped_xml(e, &scope)).transpose()?;
 
         let links = elem.get_children("link", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let categories = elem.get_children("category", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| ViaXml::from_xml(e.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let authors = elem.get_children("author", Some(NS))
-            .map(|e| {
-                let Author(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Author(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let contributors = elem.get_children("contributor", Some(NS))
-            .map(|e| {
-                let Contributor(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Contributor(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let entries = elem.get_children("entry", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let media = MediaExtension::from_xml_children(elem);
+        let podcast = PodcastFeedExtension::from_xml_children(elem);
+        let itunes = ITunesFeedExtension::from_xml_children(elem);
+        let dublin_core = DublinCoreExtension::from_xml_children(elem);
+        let georss = GeoRssExtension::from_xml_children(elem);
+        let opensearch = OpenSearchExtension::from_xml_children(elem);
+        let base = scope.base.clone();
+        let lang = scope.lang();
 
         Ok(Feed {
-            id: id,
-            title: title,
-            updated: updated,
-            icon: icon,
-            logo: logo,
-            rights: rights,
-            subtitle: subtitle,
-            generator: generator,
-            links: links,
-            categories: categories,
-            authors: authors,
-            contributors: contributors,
-            entries: entries,
+            id,
+            title,
+            updated,
+            icon,
+            logo,
+            rights,
+            subtitle,
+            generator,
+            links,
+            categories,
+            authors,
+            contributors,
+            entries,
+            media,
+            podcast,
+            itunes,
+            dublin_core,
+            georss,
+            opensearch,
+            base,
+            lang,
         })
     }
 }
 
 
+impl ResolveUris for Feed {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+
+        ::resolve_optional(&mut self.icon, &base);
+        ::resolve_optional(&mut self.logo, &base);
+
+        if let Some(ref mut generator) = self.generator {
+            generator.resolve_uris(document_url, &base);
+        }
+
+        for link in &mut self.links {
+            link.resolve_uris(document_url, &base);
+        }
+
+        for person in self.authors.iter_mut().chain(self.contributors.iter_mut()) {
+            person.resolve_uris(document_url, &base);
+        }
+
+        for entry in &mut self.entries {
+            entry.resolve_uris(document_url, &base);
+        }
+    }
+}
+
+
 impl FromStr for Feed {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parser = Parser::new();
-        parser.feed_str(&s);
+        let root = ::read_root(s)?;
 
-        let mut builder = ElementBuilder::new();
+        if root.name != "feed" {
+            return Err("Document root is not an Atom <feed> element");
+        }
 
-        for event in parser {
-            if let Some(Ok(elem)) = builder.handle_event(event) {
-                return ViaXml::from_xml(elem);
-            }
+        if root.ns.as_ref().map(|ns| &ns[..]) != Some(NS) {
+            return Err("<feed> is not in the Atom namespace");
         }
 
-        Err("Atom read error")
+        ViaXml::from_xml(root)
     }
 }
 
-impl ToString for Feed {
-    fn to_string(&self) -> String {
-        let mut ret = format!("{}", Xml::PINode(r#"xml version="1.0" encoding="utf-8""#.to_string()));
-        ret.push_str(&format!("{}", self.to_xml()));
-        ret
+impl fmt::Display for Feed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_string_with(&WriteOptions::default()))
     }
 }
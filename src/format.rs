@@ -0,0 +1,240 @@
+use xml::Element;
+
+use ::{Feed, NS, ViaXml};
+use ::json;
+use ::rss::{self, RDF_NS, RSS_090_NS, RSS_10_NS};
+
+
+/// The syndication format a document was detected as by [`parse_any`](fn.parse_any.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeedFormat {
+    /// [RFC 4287](https://tools.ietf.org/html/rfc4287)
+    Atom,
+    /// RDF-based RSS 0.90
+    Rss090,
+    /// Netscape RSS 0.91
+    Rss091,
+    /// UserLand RSS 0.92, also used for the short-lived 0.93 and 0.94
+    Rss092,
+    /// RDF Site Summary 1.0
+    Rss10,
+    /// RSS 2.0
+    Rss20,
+    /// [JSON Feed](https://jsonfeed.org/) 1.0 or 1.1
+    JsonFeed,
+}
+
+impl FeedFormat {
+    fn detect(root: &Element) -> Result<Self, &'static str> {
+        let ns = root.ns.as_ref().map(|ns| &ns[..]);
+
+        match (&root.name[..], ns) {
+            ("feed", Some(NS)) => Ok(FeedFormat::Atom),
+            ("rss", None) => match root.get_attribute("version", None).map(str::trim) {
+                Some("0.91") => Ok(FeedFormat::Rss091),
+                Some("0.92") | Some("0.93") | Some("0.94") => Ok(FeedFormat::Rss092),
+                _ => Ok(FeedFormat::Rss20),
+            },
+            ("RDF", Some(RDF_NS)) => {
+                if root.get_child("channel", Some(RSS_10_NS)).is_some() {
+                    Ok(FeedFormat::Rss10)
+                } else if root.get_child("channel", Some(RSS_090_NS)).is_some() {
+                    Ok(FeedFormat::Rss090)
+                } else {
+                    Err("RDF document does not contain an RSS channel")
+                }
+            }
+            _ => Err("Document is not an Atom, RSS or JSON Feed document"),
+        }
+    }
+}
+
+
+/// Reads a document in any supported syndication format, normalizing it into a `Feed`.
+///
+/// RSS and JSON Feed dates are rewritten as RFC 3339 timestamps, and where the source format has
+/// no equivalent of a required Atom element (such as `<id>`) the closest available value is used.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{parse_any, FeedFormat};
+///
+/// let rss_str = r#"
+/// <rss version="2.0">
+///   <channel>
+///     <title>TechCrunch</title>
+///     <link>http://techcrunch.com</link>
+///     <description>Startup and Technology News</description>
+///   </channel>
+/// </rss>
+/// "#;
+///
+/// let (feed, format) = parse_any(rss_str).unwrap();
+/// assert_eq!(format, FeedFormat::Rss20);
+/// assert_eq!(feed.title, "TechCrunch");
+/// ```
+pub fn parse_any(s: &str) -> Result<(Feed, FeedFormat), &'static str> {
+    let trimmed = s.trim_start_matches('\u{feff}').trim_start();
+
+    if trimmed.starts_with('{') {
+        return json::from_json(trimmed).map(|feed| (feed, FeedFormat::JsonFeed));
+    }
+
+    let root = ::read_root(trimmed)?;
+    let format = FeedFormat::detect(&root)?;
+
+    let feed = match format {
+        FeedFormat::Atom => ViaXml::from_xml(root)?,
+        _ => rss::from_rss(&root, format)?,
+    };
+
+    Ok((feed, format))
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{parse_any, FeedFormat};
+    use ::{Length, LinkRel, Text};
+
+    #[test]
+    fn test_parse_any_atom() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+                <title>TechCrunch</title>
+                <updated>2019-04-01T07:30:00Z</updated>
+            </feed>"#;
+        let (feed, format) = parse_any(atom_str).unwrap();
+        assert_eq!(format, FeedFormat::Atom);
+        assert_eq!(feed.id, "urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6");
+    }
+
+    #[test]
+    fn test_parse_any_malformed_atom() {
+        let head = r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:feed</id><title>T</title><updated/>"#;
+
+        let no_entry_id = format!("{}<entry><title>T</title><updated/></entry></feed>", head);
+        assert_eq!(parse_any(&no_entry_id).err(), Some("<entry> is missing required <id> element"));
+
+        let no_href = format!("{}<link rel=\"self\"/></feed>", head);
+        assert_eq!(parse_any(&no_href).err(), Some(r#"<link> is missing required "href" element"#));
+
+        let no_author_name = format!("{}<entry><id>1</id><title>T</title><updated/><author/></entry></feed>", head);
+        assert_eq!(parse_any(&no_author_name).err(), Some("<author> is missing required <name> element"));
+    }
+
+    #[test]
+    fn test_parse_any_rss20() {
+        let rss_str = r#"<?xml version="1.0"?>
+            <rss version="2.0">
+                <channel>
+                    <title>My Blog</title>
+                    <link>http://test.blog/</link>
+                    <description>Posts</description>
+                    <managingEditor>editor@test.blog (N. Blogger)</managingEditor>
+                    <item>
+                        <title>First</title>
+                        <link>http://test.blog/first</link>
+                        <guid>http://test.blog/?p=1</guid>
+                        <pubDate>Mon, 01 Apr 2019 07:30:00 GMT</pubDate>
+                        <enclosure url="http://test.blog/first.mp3" length="1024" type="audio/mpeg"/>
+                    </item>
+                </channel>
+            </rss>"#;
+        let (feed, format) = parse_any(rss_str).unwrap();
+        assert_eq!(format, FeedFormat::Rss20);
+        assert_eq!(feed.id, "http://test.blog/");
+        assert_eq!(feed.subtitle, Some("Posts".into()));
+        assert_eq!(feed.updated, "2019-04-01T07:30:00Z");
+        assert_eq!(feed.authors[0].name, "N. Blogger");
+        assert_eq!(feed.authors[0].email, Some("editor@test.blog".to_string()));
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.id, "http://test.blog/?p=1");
+        assert_eq!(entry.updated, "2019-04-01T07:30:00Z");
+        assert_eq!(entry.links[1].rel, Some(LinkRel::Enclosure));
+        assert_eq!(entry.links[1].length, Some(Length::Bytes(1024)));
+    }
+
+    #[test]
+    fn test_parse_any_rss_versions() {
+        let rss_str = r#"<rss version="0.91"><channel><title>Old</title></channel></rss>"#;
+        assert_eq!(parse_any(rss_str).unwrap().1, FeedFormat::Rss091);
+
+        let rss_str = r#"<rss version="0.92"><channel><title>Old</title></channel></rss>"#;
+        assert_eq!(parse_any(rss_str).unwrap().1, FeedFormat::Rss092);
+
+        let rss_str = r#"
+            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+                     xmlns="http://my.netscape.com/rdf/simple/0.9/">
+                <channel><title>Mozilla Dot Org</title><link>http://www.mozilla.org</link></channel>
+                <item><title>New Status Updates</title><link>http://www.mozilla.org/status/</link></item>
+            </rdf:RDF>"#;
+        let (feed, format) = parse_any(rss_str).unwrap();
+        assert_eq!(format, FeedFormat::Rss090);
+        assert_eq!(feed.entries[0].id, "http://www.mozilla.org/status/");
+    }
+
+    #[test]
+    fn test_parse_any_rss10() {
+        let rss_str = r#"
+            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+                     xmlns:dc="http://purl.org/dc/elements/1.1/"
+                     xmlns="http://purl.org/rss/1.0/">
+                <channel rdf:about="http://xml.com/xml/news.rss">
+                    <title>XML.com</title>
+                    <link>http://xml.com/pub</link>
+                </channel>
+                <item rdf:about="http://xml.com/pub/2000/08/09/xslt/xslt.html">
+                    <title>Processing Inclusions with XSLT</title>
+                    <dc:date>2000-08-09T12:00:00+02:00</dc:date>
+                </item>
+            </rdf:RDF>"#;
+        let (feed, format) = parse_any(rss_str).unwrap();
+        assert_eq!(format, FeedFormat::Rss10);
+        assert_eq!(feed.updated, "2000-08-09T12:00:00+02:00");
+        assert_eq!(feed.entries[0].id, "http://xml.com/pub/2000/08/09/xslt/xslt.html");
+    }
+
+    #[test]
+    fn test_parse_any_json_feed() {
+        let json_str = r#"{
+            "version": "https://jsonfeed.org/version/1.1",
+            "title": "My Example Feed",
+            "home_page_url": "https://example.org/",
+            "feed_url": "https://example.org/feed.json",
+            "authors": [{ "name": "N. Blogger" }],
+            "items": [
+                {
+                    "id": "2",
+                    "content_text": "This is a second item.",
+                    "url": "https://example.org/second-item",
+                    "date_published": "2019-04-01T07:30:00Z"
+                },
+                {
+                    "id": 1,
+                    "content_html": "<p>Hello, world!</p>",
+                    "date_published": "2019-03-01T07:30:00Z",
+                    "tags": ["greeting"]
+                }
+            ]
+        }"#;
+        let (feed, format) = parse_any(json_str).unwrap();
+        assert_eq!(format, FeedFormat::JsonFeed);
+        assert_eq!(feed.id, "https://example.org/feed.json");
+        assert_eq!(feed.updated, "2019-04-01T07:30:00Z");
+        assert_eq!(feed.authors[0].name, "N. Blogger");
+        assert_eq!(feed.entries[1].id, "1");
+        assert_eq!(feed.entries[1].content, Some(Text::html("<p>Hello, world!</p>")));
+        assert_eq!(feed.entries[1].categories[0].term, "greeting");
+    }
+
+    #[test]
+    fn test_parse_any_unknown() {
+        assert!(parse_any("<html><body/></html>").is_err());
+        assert!(parse_any(r#"{"title": "not a feed"}"#).is_err());
+        assert!(parse_any("").is_err());
+    }
+}
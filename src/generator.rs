@@ -2,16 +2,18 @@ use std::convert::AsRef;
 
 use xml::Element;
 
-use ::{ElementUtils, NS, ViaXml};
+use ::{ElementUtils, FromScopedXml, NS, ResolveUris, ViaXml, XmlScope};
 
 
 /// [The Atom Syndication Format § The "atom:generator" Element]
 /// (https://tools.ietf.org/html/rfc4287#section-4.2.4)
-#[derive(Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Generator {
     pub name: String,
     pub uri: Option<String>,
     pub version: Option<String>,
+    /// The effective `xml:base` of the generator, which `uri` may be relative to
+    pub base: Option<String>,
 }
 
 
@@ -23,11 +25,21 @@ impl ViaXml for Generator {
 
         link.attribute_with_optional_text("uri", &self.uri);
         link.attribute_with_optional_text("version", &self.version);
+        link.xml_attribute_with_optional_text("base", &self.base);
 
         link
     }
 
     fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Generator::from_scoped_xml(&elem, &XmlScope::default())
+    }
+}
+
+
+impl FromScopedXml for Generator {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str> {
+        let scope = parent.of(elem);
+
         let name = match elem.content_str().as_ref() {
             "" => return Err(r#"<generator> is missing required name"#),
             n => n.to_string(),
@@ -35,11 +47,21 @@ impl ViaXml for Generator {
 
         let uri = elem.get_attribute("uri", None).map(String::from);
         let version = elem.get_attribute("version", None).map(String::from);
+        let base = scope.base.clone();
 
         Ok(Generator {
-            name: name,
-            uri: uri,
-            version: version,
+            name,
+            uri,
+            version,
+            base,
         })
     }
 }
+
+
+impl ResolveUris for Generator {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+        ::resolve_optional(&mut self.uri, &base);
+    }
+}
@@ -0,0 +1,152 @@
+use serde_json::{self, Map, Value};
+
+use ::{Category, Entry, Feed, Length, Link, LinkRel, MediaType, Person, Text};
+use ::date;
+
+
+const VERSION_PREFIX: &str = "https://jsonfeed.org/version/";
+
+
+/// Converts a [JSON Feed](https://jsonfeed.org/version/1.1) document into a `Feed`.
+pub fn from_json(s: &str) -> Result<Feed, &'static str> {
+    let value: Value = match serde_json::from_str(s) {
+        Ok(value) => value,
+        Err(_) => return Err("JSON Feed read error"),
+    };
+
+    let feed = match value.as_object() {
+        Some(feed) => feed,
+        None => return Err("JSON Feed must be an object"),
+    };
+
+    match string(feed, "version") {
+        Some(ref v) if v.starts_with(VERSION_PREFIX) => (),
+        _ => return Err(r#"JSON Feed is missing required "version" member"#),
+    }
+
+    let title = match string(feed, "title") {
+        Some(title) => title,
+        None => return Err(r#"JSON Feed is missing required "title" member"#),
+    };
+
+    let entries: Vec<Entry> = match feed.get("items").and_then(Value::as_array) {
+        Some(items) => items.iter().filter_map(Value::as_object).map(entry).collect(),
+        None => return Err(r#"JSON Feed is missing required "items" member"#),
+    };
+
+    let home_page_url = string(feed, "home_page_url");
+    let feed_url = string(feed, "feed_url");
+
+    let mut links = Vec::new();
+    if let Some(ref href) = home_page_url {
+        links.push(link(href, LinkRel::Alternate));
+    }
+    if let Some(ref href) = feed_url {
+        links.push(link(href, LinkRel::Self_));
+    }
+
+    let updated = entries.iter().map(|e| e.updated.clone()).fold(String::new(), date::latest);
+
+    Ok(Feed {
+        id: feed_url.or(home_page_url).unwrap_or_else(|| title.clone()),
+        title: title.into(),
+        updated,
+        icon: string(feed, "favicon"),
+        logo: string(feed, "icon"),
+        subtitle: string(feed, "description").map(Text::from),
+        links,
+        authors: authors(feed),
+        entries,
+        lang: string(feed, "language"),
+        ..Default::default()
+    })
+}
+
+
+fn entry(item: &Map<String, Value>) -> Entry {
+    let published = string(item, "date_published").map(|d| date::normalize(&d));
+    let updated = string(item, "date_modified").map(|d| date::normalize(&d));
+
+    let mut links = Vec::new();
+    if let Some(href) = string(item, "url") {
+        links.push(link(&href, LinkRel::Alternate));
+    }
+    if let Some(href) = string(item, "external_url") {
+        links.push(link(&href, LinkRel::Related));
+    }
+
+    if let Some(attachments) = item.get("attachments").and_then(Value::as_array) {
+        links.extend(attachments.iter().filter_map(Value::as_object).filter_map(|a| {
+            string(a, "url").map(|href| Link {
+                href,
+                rel: Some(LinkRel::Enclosure),
+                mediatype: string(a, "mime_type").map(MediaType::from),
+                title: string(a, "title"),
+                length: a.get("size_in_bytes").and_then(Value::as_u64).map(Length::Bytes),
+                ..Default::default()
+            })
+        }));
+    }
+
+    let categories = item.get("tags").and_then(Value::as_array)
+        .map(|tags| {
+            tags.iter().filter_map(Value::as_str).map(|term| Category {
+                term: term.to_string(),
+                ..Default::default()
+            }).collect()
+        })
+        .unwrap_or_default();
+
+    let id = match item.get("id") {
+        Some(Value::String(id)) => id.clone(),
+        Some(Value::Number(id)) => id.to_string(),
+        _ => String::new(),
+    };
+
+    Entry {
+        id,
+        title: string(item, "title").unwrap_or_default().into(),
+        updated: updated.or_else(|| published.clone()).unwrap_or_default(),
+        published,
+        links,
+        categories,
+        authors: authors(item),
+        summary: string(item, "summary").map(Text::from),
+        content: string(item, "content_html").map(Text::html).or_else(|| string(item, "content_text").map(Text::from)),
+        lang: string(item, "language"),
+        ..Default::default()
+    }
+}
+
+
+/// Version 1.1 uses an `authors` array, version 1.0 a single `author` object.
+fn authors(obj: &Map<String, Value>) -> Vec<Person> {
+    let objects: Vec<&Map<String, Value>> = match obj.get("authors").and_then(Value::as_array) {
+        Some(authors) => authors.iter().filter_map(Value::as_object).collect(),
+        None => obj.get("author").and_then(Value::as_object).into_iter().collect(),
+    };
+
+    objects.into_iter()
+        .filter_map(|a| {
+            string(a, "name").or_else(|| string(a, "url")).map(|name| Person {
+                name,
+                uri: string(a, "url"),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+
+fn link(href: &str, rel: LinkRel) -> Link {
+    Link {
+        href: href.to_string(),
+        rel: Some(rel),
+        ..Default::default()
+    }
+}
+
+
+fn string(obj: &Map<String, Value>, key: &str) -> Option<String> {
+    obj.get(key).and_then(Value::as_str).map(String::from)
+}
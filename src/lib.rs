@@ -23,14 +23,14 @@
 //!
 //! let entry = Entry {
 //!     id: String::from("urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac"),
-//!     title: String::from("Ford hires Elon Musk as CEO"),
+//!     title: "Ford hires Elon Musk as CEO".into(),
 //!     updated: String::from("2019-04-01T07:30:00Z"),
 //!     ..Default::default()
 //! };
 //!
 //! let feed = Feed {
 //!     id: String::from("urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6"),
-//!     title: String::from("TechCrunch"),
+//!     title: "TechCrunch".into(),
 //!     updated: String::from("2019-04-01T07:30:00Z"),
 //!     entries: vec![entry],
 //!     ..Default::default()
@@ -60,33 +60,81 @@
 //!
 //! let feed = atom_str.parse::<Feed>().unwrap();
 //! ```
+//!
+//! ## Reading other formats
+//!
+//! RSS 0.9x, 1.0 and 2.0 and JSON Feed documents can be read into a `Feed` with `parse_any`,
+//! which also reports which format was detected.
+//!
+//! ```
+//! use atom_syndication::{parse_any, FeedFormat};
+//!
+//! let rss_str = r#"<rss version="2.0"><channel><title>TechCrunch</title></channel></rss>"#;
+//!
+//! let (feed, format) = parse_any(rss_str).unwrap();
+//! assert_eq!(format, FeedFormat::Rss20);
+//! ```
+
+pub mod aggregate;
+pub mod extension;
+pub mod sanitize;
+pub mod state;
+pub mod validate;
+pub mod websub;
 
 mod author;
 mod category;
+mod category_index;
 mod contributor;
+mod date;
+mod diff;
+mod enclosure;
+mod encoding;
 mod entry;
 mod feed;
+mod format;
 mod generator;
+mod json;
 mod link;
 mod person;
+mod rss;
 mod source;
+mod text;
+mod uri;
+mod writer;
 
+extern crate chrono;
+extern crate encoding_rs;
+extern crate hmac;
+extern crate serde_json;
+extern crate sha1;
+extern crate sha2;
 extern crate xml;
 
-use xml::Element;
+use xml::{Element, ElementBuilder, Parser, Xml};
 
 pub use ::author::Author;
 pub use ::category::Category;
+pub use ::category_index::{CaseFolding, CategoryIndex, IndexOptions, IndexedCategory, LabelMerge};
 pub use ::contributor::Contributor;
+pub use ::diff::{EntryChange, EntryField, FeedDiff, FeedField};
+pub use ::enclosure::Enclosure;
 pub use ::entry::Entry;
 pub use ::feed::Feed;
+pub use ::format::{parse_any, FeedFormat};
 pub use ::generator::Generator;
-pub use ::link::Link;
+pub use ::link::{Length, Link, LinkPreferences, LinkRel, MediaType};
 pub use ::person::Person;
 pub use ::source::Source;
+pub use ::text::{Text, TextType};
+pub use ::writer::{OutputEncoding, QuoteStyle, WriteOptions};
+
+pub use encoding_rs::Encoding;
 
 
-const NS: &'static str = "http://www.w3.org/2005/Atom";
+const NS: &str = "http://www.w3.org/2005/Atom";
+const XML_NS: &str = "http://www.w3.org/XML/1998/namespace";
+const XHTML_NS: &str = "http://www.w3.org/1999/xhtml";
 
 
 trait ElementUtils {
@@ -94,6 +142,9 @@ trait ElementUtils {
     fn tag_with_optional_text(&mut self, child_name: &'static str, child_body: &Option<String>);
     fn attribute_with_text(&mut self, attribute_name: &'static str, attribute_value: &str);
     fn attribute_with_optional_text(&mut self, attribute_name: &'static str, attribute_value: &Option<String>);
+    fn xml_attribute_with_optional_text(&mut self, attribute_name: &'static str, attribute_value: &Option<String>);
+    fn tag_with_text_construct(&mut self, child_name: &'static str, child_body: &Text);
+    fn tag_with_optional_text_construct(&mut self, child_name: &'static str, child_body: &Option<Text>);
 }
 
 
@@ -104,7 +155,7 @@ impl ElementUtils for Element {
 
     fn tag_with_optional_text(&mut self, child_name: &'static str, child_body: &Option<String>) {
         if let Some(ref c) = *child_body {
-            self.tag_with_text(child_name, &c);
+            self.tag_with_text(child_name, c);
         }
     }
 
@@ -114,7 +165,23 @@ impl ElementUtils for Element {
 
     fn attribute_with_optional_text(&mut self, attribute_name: &'static str, attribute_value: &Option<String>) {
         if let Some(ref v) = *attribute_value {
-            self.attribute_with_text(attribute_name, &v);
+            self.attribute_with_text(attribute_name, v);
+        }
+    }
+
+    fn xml_attribute_with_optional_text(&mut self, attribute_name: &'static str, attribute_value: &Option<String>) {
+        if let Some(ref v) = *attribute_value {
+            self.set_attribute(attribute_name.to_string(), Some(XML_NS.to_string()), v.clone());
+        }
+    }
+
+    fn tag_with_text_construct(&mut self, child_name: &'static str, child_body: &Text) {
+        self.tag(text::to_xml(child_name, child_body));
+    }
+
+    fn tag_with_optional_text_construct(&mut self, child_name: &'static str, child_body: &Option<Text>) {
+        if let Some(ref t) = *child_body {
+            self.tag_with_text_construct(child_name, t);
         }
     }
 }
@@ -127,19 +194,126 @@ fn elem_with_text(tag_name: &'static str, chars: &str) -> Element {
 }
 
 
+/// Parses `s` up to the end of its first complete element.
+fn read_root(s: &str) -> Result<Element, &'static str> {
+    let mut parser = Parser::new();
+    parser.feed_str(s);
+
+    let mut builder = ElementBuilder::new();
+
+    for event in parser {
+        match builder.handle_event(event) {
+            Some(Ok(elem)) => return Ok(elem),
+            Some(Err(_)) => return Err("XML read error"),
+            None => (),
+        }
+    }
+
+    Err("Document does not contain a root element")
+}
+
+
+/// The `xml:base` and `xml:lang` in effect at an element, which it inherits from its ancestors
+/// unless it sets its own.
+#[derive(Clone, Debug, Default)]
+struct XmlScope {
+    base: Option<String>,
+    lang: Option<String>,
+}
+
+impl XmlScope {
+    /// The scope of `elem`, whose parent has this scope.
+    fn of(&self, elem: &Element) -> XmlScope {
+        let base = match (self.base.as_ref(), elem.get_attribute("base", Some(XML_NS))) {
+            (Some(parent), Some(own)) => Some(uri::resolve(parent, own)),
+            (None, Some(own)) => Some(own.to_string()),
+            (parent, None) => parent.cloned(),
+        };
+
+        let lang = elem.get_attribute("lang", Some(XML_NS))
+            .map(String::from)
+            .or_else(|| self.lang.clone());
+
+        XmlScope { base, lang }
+    }
+
+    /// The language, where an empty `xml:lang` means it is unknown.
+    fn lang(&self) -> Option<String> {
+        self.lang.clone().filter(|lang| !lang.is_empty())
+    }
+}
+
+
+/// Drops `xml:` attributes that repeat the value inherited from an ancestor.
+fn prune_inherited(elem: &mut Element, name: &str, inherited: Option<&str>) {
+    let value = elem.get_attribute(name, Some(XML_NS)).map(String::from);
+
+    if value.is_some() && value.as_ref().map(|v| &v[..]) == inherited {
+        elem.remove_attribute(name, Some(XML_NS));
+    }
+
+    let effective = value.as_ref().map(|v| &v[..]).or(inherited);
+
+    for child in &mut elem.children {
+        if let Xml::ElementNode(ref mut child) = *child {
+            prune_inherited(child, name, effective);
+        }
+    }
+}
+
+
+/// Constructs holding IRIs that may be relative to their effective `xml:base`.
+trait ResolveUris {
+    /// `base` is the construct's effective base as read from the document, which is relative to
+    /// `document_url`; constructs without one inherit `parent_base`.
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str);
+}
+
+
+fn resolve_base(base: &mut Option<String>, document_url: &str, parent_base: &str) -> String {
+    let resolved = match *base {
+        Some(ref b) => uri::resolve(document_url, b),
+        None => parent_base.to_string(),
+    };
+    *base = Some(resolved.clone());
+    resolved
+}
+
+
+fn resolve_optional(iri: &mut Option<String>, base: &str) {
+    if let Some(ref mut iri) = *iri {
+        *iri = uri::resolve(base, iri);
+    }
+}
+
+
 trait ViaXml where Self: Sized {
     fn to_xml(&self) -> Element;
     fn from_xml(elem: Element) -> Result<Self, &'static str>;
 }
 
 
+/// Atom constructs, which are read knowing the `xml:base` and `xml:lang` of their parent.
+trait FromScopedXml where Self: Sized {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str>;
+}
+
+
+/// Extension data made up of several children of a feed or entry, rather than a single element.
+trait ViaXmlChildren where Self: Sized {
+    fn append_xml(&self, parent: &mut Element);
+    /// Returns `None` when `parent` has none of the extension's elements.
+    fn from_xml_children(parent: &Element) -> Option<Self>;
+}
+
+
 #[cfg(test)]
 mod test {
     use std::default::Default;
     use std::fs::File;
     use std::io::Read;
     use std::str::FromStr;
-    use super::{Person, Entry, Feed, Link};
+    use super::{Person, Entry, Feed, Link, LinkRel, Source, Text, TextType};
 
     #[test]
     fn test_basic_to_string() {
@@ -149,13 +323,13 @@ mod test {
         };
 
         let entry = Entry {
-            title: "My first post!".to_string(),
-            content: Some("This is my first post".to_string()),
+            title: "My first post!".into(),
+            content: Some("This is my first post".into()),
             ..Default::default()
         };
 
         let feed = Feed {
-            title: "My Blog".to_string(),
+            title: "My Blog".into(),
             authors: vec![author],
             entries: vec![entry],
             ..Default::default()
@@ -170,7 +344,7 @@ mod test {
             links: vec![
                 Link {
                     href: "http://test.blog/blog.atom".to_string(),
-                    rel: Some("self".to_string()),
+                    rel: Some(LinkRel::Self_),
                     ..Default::default()
                 },
             ],
@@ -179,16 +353,16 @@ mod test {
                     links: vec![
                         Link {
                             href: "http://test.blog/entry".to_string(),
-                            rel: Some("alternate".to_string()),
+                            rel: Some(LinkRel::Alternate),
                             ..Default::default()
                         }
                     ],
-                    source: Some(Feed {
-                        title: "Original Blog".to_string(),
+                    source: Some(Source {
+                        title: Some("Original Blog".into()),
                         links: vec![
                             Link {
                                 href: "http://original.blog/feed.atom".to_string(),
-                                rel: Some("self".to_string()),
+                                rel: Some(LinkRel::Self_),
                                 ..Default::default()
                             }
                         ],
@@ -200,7 +374,12 @@ mod test {
             ..Default::default()
         };
 
-        assert!(feed.to_string().bytes().count() > 0);
+        assert!(!feed.to_string().is_empty());
+
+        let read = Feed::from_str(&feed.to_string()).unwrap();
+        let source = read.entries[0].source.as_ref().unwrap();
+        assert_eq!(source.title, Some("Original Blog".into()));
+        assert_eq!(source.links[0].href, "http://original.blog/feed.atom");
     }
 
     #[test]
@@ -209,7 +388,7 @@ mod test {
         let mut atom_string = String::new();
         file.read_to_string(&mut atom_string).unwrap();
         let feed = Feed::from_str(&atom_string).unwrap();
-        assert!(feed.to_string().len() > 0);
+        assert!(!feed.to_string().is_empty());
     }
 
     #[test]
@@ -223,7 +402,36 @@ mod test {
         let atom_str = "\
             <feed>\
             </feed>";
-        assert!(Feed::from_str(atom_str).is_err());
+        assert_eq!(Feed::from_str(atom_str).err(), Some("<feed> is not in the Atom namespace"));
+    }
+
+    #[test]
+    fn test_read_non_atom_root() {
+        let atom_str = r#"
+            <rss xmlns="http://www.w3.org/2005/Atom">
+                <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+                <title>Hello world!</title>
+                <updated></updated>
+            </rss>"#;
+        assert_eq!(Feed::from_str(atom_str).err(), Some("Document root is not an Atom <feed> element"));
+    }
+
+    #[test]
+    fn test_read_prefixed_feed() {
+        let atom_str = r#"
+            <atom:feed xmlns:atom="http://www.w3.org/2005/Atom">
+                <atom:id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</atom:id>
+                <atom:title>Hello world!</atom:title>
+                <atom:updated></atom:updated>
+                <atom:entry>
+                    <atom:id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</atom:id>
+                    <atom:title>First post</atom:title>
+                    <atom:updated></atom:updated>
+                </atom:entry>
+            </atom:feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!("Hello world!", feed.title);
+        assert_eq!("First post", feed.entries[0].title);
     }
 
     #[test]
@@ -240,6 +448,184 @@ mod test {
         assert_eq!("Hello world!", feed.title);
     }
 
+    #[test]
+    fn test_xml_base() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:base="http://test.blog/">
+                <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+                <title>My Blog</title>
+                <updated></updated>
+                <logo>logo.png</logo>
+                <entry xml:base="posts/">
+                    <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+                    <title>First post</title>
+                    <updated></updated>
+                    <link href="42"/>
+                    <author><name>N. Blogger</name><uri>../about</uri></author>
+                </entry>
+            </feed>"#;
+        let mut feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.entries[0].base, Some("http://test.blog/posts/".to_string()));
+        assert_eq!(feed.entries[0].links[0].base, Some("http://test.blog/posts/".to_string()));
+        assert_eq!(feed.entries[0].links[0].href, "42");
+
+        let atom_string = feed.to_string();
+        assert_eq!(atom_string.matches("xml:base").count(), 2);
+
+        feed.resolve_relative_uris("http://ignored.example/feed.atom");
+        assert_eq!(feed.logo, Some("http://test.blog/logo.png".to_string()));
+        assert_eq!(feed.entries[0].links[0].href, "http://test.blog/posts/42");
+        assert_eq!(feed.entries[0].authors[0].uri, Some("http://test.blog/about".to_string()));
+    }
+
+    #[test]
+    fn test_content_src_and_source_base() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:base="http://test.blog/">
+                <id>urn:feed</id>
+                <title>My Blog</title>
+                <updated></updated>
+                <entry xml:base="posts/">
+                    <id>urn:entry</id>
+                    <title>Episode</title>
+                    <updated></updated>
+                    <source xml:base="http://original.blog/blog/">
+                        <link rel="self" href="feed.atom"/>
+                    </source>
+                    <content type="audio/mpeg" src="42.mp3"/>
+                </entry>
+            </feed>"#;
+        let mut feed = Feed::from_str(atom_str).unwrap();
+        {
+            let entry = &feed.entries[0];
+            let content = entry.content.as_ref().unwrap();
+            assert_eq!(content.src, Some("42.mp3".to_string()));
+            assert_eq!(content.mediatype.as_ref().map(|m| m.as_str()), Some("audio/mpeg"));
+            assert_eq!(entry.source.as_ref().unwrap().base, Some("http://original.blog/blog/".to_string()));
+        }
+
+        let reread = Feed::from_str(&feed.to_string()).unwrap();
+        assert_eq!(reread.entries[0].content, feed.entries[0].content);
+
+        feed.resolve_relative_uris("http://ignored.example/feed.atom");
+        let entry = &feed.entries[0];
+        assert_eq!(entry.content.as_ref().unwrap().src, Some("http://test.blog/posts/42.mp3".to_string()));
+        assert_eq!(entry.source.as_ref().unwrap().links[0].href, "http://original.blog/blog/feed.atom");
+    }
+
+    #[test]
+    fn test_relative_bases() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:base="../">
+                <id>urn:feed</id>
+                <title>My Blog</title>
+                <updated></updated>
+                <entry xml:base="posts/">
+                    <id>urn:entry</id>
+                    <title>Post</title>
+                    <updated></updated>
+                    <link href="42"/>
+                </entry>
+            </feed>"#;
+        let mut feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.entries[0].base, Some("../posts/".to_string()));
+        assert_eq!(feed.entries[0].links[0].base, Some("../posts/".to_string()));
+
+        feed.resolve_relative_uris("http://ex.com/a/feed.atom");
+        assert_eq!(feed.entries[0].links[0].href, "http://ex.com/posts/42");
+    }
+
+    #[test]
+    fn test_text_types_round_trip() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <id>urn:feed</id>
+                <title type="html">Tom &amp;amp; Jerry</title>
+                <updated></updated>
+                <entry>
+                    <id>urn:entry</id>
+                    <title>1 &lt; 2</title>
+                    <updated></updated>
+                    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p class="x">A &amp; <em>B</em></p><br/></div></content>
+                </entry>
+            </feed>"#;
+        let mut feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.title, Text::html("Tom &amp; Jerry"));
+        assert_eq!(feed.entries[0].title, Text::from("1 < 2"));
+
+        let content = feed.entries[0].content.clone().unwrap();
+        assert_eq!(content.text_type, TextType::Xhtml);
+        assert_eq!(content, r#"<p class="x">A &amp; <em>B</em></p><br/>"#);
+
+        let reread = Feed::from_str(&feed.to_string()).unwrap();
+        assert_eq!(reread.title, feed.title);
+        assert_eq!(reread.entries[0].title, feed.entries[0].title);
+        assert_eq!(reread.entries[0].content, Some(content));
+
+        // Markup that isn't well formed is written as HTML instead
+        feed.entries[0].content = Some(Text { value: "<p>Unclosed".to_string(), text_type: TextType::Xhtml, ..Default::default() });
+        let reread = Feed::from_str(&feed.to_string()).unwrap();
+        assert_eq!(reread.entries[0].content, Some(Text::html("<p>Unclosed")));
+    }
+
+    #[test]
+    fn test_xhtml_content_in_scope() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:base="http://test.blog/" xml:lang="en">
+                <id>urn:feed</id>
+                <title>My Blog</title>
+                <updated></updated>
+                <entry>
+                    <id>urn:entry</id>
+                    <title>Post</title>
+                    <updated></updated>
+                    <content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>A <em xml:lang="fr">B</em></p></div></content>
+                </entry>
+            </feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        let content = feed.entries[0].content.clone().unwrap();
+        assert_eq!(content.value, r#"<p>A <em xml:lang="fr">B</em></p>"#);
+        assert_eq!(content.lang, Some("en".to_string()));
+
+        let reread = Feed::from_str(&feed.to_string()).unwrap();
+        assert_eq!(reread.entries[0].content, Some(content));
+    }
+
+    #[test]
+    fn test_xml_lang() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+                <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+                <title>My Blog</title>
+                <updated></updated>
+                <entry xml:lang="fr">
+                    <id>urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac</id>
+                    <title>Mon premier billet</title>
+                    <updated></updated>
+                    <content xml:lang="de">Mein erster Beitrag</content>
+                </entry>
+                <entry>
+                    <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+                    <title>My second post</title>
+                    <updated></updated>
+                </entry>
+            </feed>"#;
+        let feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.lang, Some("en".to_string()));
+        assert_eq!(feed.title.lang, Some("en".to_string()));
+        assert_eq!(feed.entries[0].lang, Some("fr".to_string()));
+        assert_eq!(feed.entries[0].title.lang, Some("fr".to_string()));
+        assert_eq!(feed.entries[0].content.as_ref().unwrap().lang, Some("de".to_string()));
+        assert_eq!(feed.entries[1].title.lang, Some("en".to_string()));
+
+        let atom_string = feed.to_string();
+        assert_eq!(atom_string.matches("xml:lang").count(), 3);
+
+        let by_lang = feed.entries_by_lang();
+        assert_eq!(by_lang.len(), 2);
+        assert_eq!(by_lang[&Some("fr".to_string())][0].title, "Mon premier billet");
+    }
+
     // Ensure reader ignores the PI XML node and continues to parse the feed
     #[test]
     fn test_read_with_pinode() {
@@ -254,4 +640,41 @@ mod test {
         let feed = Feed::from_str(atom_str).unwrap();
         assert_eq!("Title", feed.title);
     }
+
+    #[test]
+    fn test_author_and_rights_inheritance() {
+        let person = |name: &str| Person { name: name.to_string(), ..Default::default() };
+
+        let mut feed = Feed {
+            authors: vec![person("Feed Author")],
+            rights: Some("© Feed".into()),
+            entries: vec![
+                Entry { authors: vec![person("Entry Author")], rights: Some("CC BY".into()), ..Default::default() },
+                Entry {
+                    source: Some(Source {
+                        authors: vec![person("Source Author")],
+                        rights: Some("© Source".into()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                Entry::default(),
+            ],
+            ..Default::default()
+        };
+
+        let authors = feed.entries.iter()
+            .map(|entry| feed.effective_authors(entry)[0].name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(authors, vec!["Entry Author", "Source Author", "Feed Author"]);
+
+        let rights = feed.entries.iter()
+            .map(|entry| feed.effective_rights(entry).unwrap().value.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(rights, vec!["CC BY", "© Feed", "© Feed"]);
+
+        feed.apply_inheritance();
+        assert_eq!(feed.entries[2].authors, vec![person("Feed Author")]);
+        assert_eq!(feed.entries[1].rights, Some("© Feed".into()));
+    }
 }
@@ -1,18 +1,50 @@
+use std::fmt;
+
 use xml::Element;
 
-use ::{ElementUtils, NS, ViaXml};
+use ::{ElementUtils, FromScopedXml, NS, ResolveUris, ViaXml, XmlScope};
 
 
 /// [The Atom Syndication Format § The "atom:link" Element]
 /// (https://tools.ietf.org/html/rfc4287#section-4.2.7)
-#[derive(Default)]
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{Length, Link, LinkRel};
+///
+/// let link = Link {
+///     href: String::from("http://test.blog/episode-1.mp3"),
+///     rel: Some(LinkRel::Enclosure),
+///     mediatype: Some("audio/mpeg".into()),
+///     length: Some(Length::Bytes(24986239)),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(link.mediatype.unwrap().main_type(), "audio");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Link {
     pub href: String,
-    pub rel: Option<String>,
-    pub mediatype: Option<String>,
+    pub rel: Option<LinkRel>,
+    pub mediatype: Option<MediaType>,
     pub hreflang: Option<String>,
     pub title: Option<String>,
-    pub length: Option<String>,
+    /// The size of the linked resource
+    pub length: Option<Length>,
+    /// The effective `xml:base` of the link, which `href` may be relative to
+    pub base: Option<String>,
+}
+
+
+impl Link {
+    /// Whether the link has the relation `rel`, counting a link with no `rel` as `alternate`.
+    pub fn has_rel(&self, rel: &LinkRel) -> bool {
+        match self.rel {
+            Some(ref own) => own == rel,
+            None => *rel == LinkRel::Alternate,
+        }
+    }
 }
 
 
@@ -22,34 +54,441 @@ impl ViaXml for Link {
 
         link.attribute_with_text("href", &self.href);
 
-        link.attribute_with_optional_text("rel", &self.rel);
-        link.attribute_with_optional_text("type", &self.mediatype);
+        link.attribute_with_optional_text("rel", &self.rel.as_ref().map(|r| r.to_string()));
+        link.attribute_with_optional_text("type", &self.mediatype.as_ref().map(|m| m.to_string()));
         link.attribute_with_optional_text("hreflang", &self.hreflang);
         link.attribute_with_optional_text("title", &self.title);
-        link.attribute_with_optional_text("length", &self.length);
+        link.attribute_with_optional_text("length", &self.length.as_ref().map(|l| l.to_string()));
+        link.xml_attribute_with_optional_text("base", &self.base);
 
         link
     }
 
     fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Link::from_scoped_xml(&elem, &XmlScope::default())
+    }
+}
+
+
+impl FromScopedXml for Link {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str> {
+        let scope = parent.of(elem);
+
         let href = match elem.get_attribute("href", None) {
             Some(attr) => attr.to_string(),
             None => return Err(r#"<link> is missing required "href" element"#),
         };
 
-        let rel = elem.get_attribute("rel", None).map(String::from);
-        let mediatype = elem.get_attribute("type", None).map(String::from);
+        let rel = elem.get_attribute("rel", None).map(LinkRel::from);
+        let mediatype = elem.get_attribute("type", None).map(MediaType::from);
         let hreflang = elem.get_attribute("hreflang", None).map(String::from);
         let title = elem.get_attribute("title", None).map(String::from);
-        let length = elem.get_attribute("length", None).map(String::from);
+        let length = elem.get_attribute("length", None).map(Length::from);
+        let base = scope.base.clone();
 
         Ok(Link {
-            href: href,
-            rel: rel,
-            mediatype: mediatype,
-            hreflang: hreflang,
-            title: title,
-            length: length,
+            href,
+            rel,
+            mediatype,
+            hreflang,
+            title,
+            length,
+            base,
+        })
+    }
+}
+
+
+impl ResolveUris for Link {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+        self.href = ::uri::resolve(&base, &self.href);
+    }
+}
+
+
+/// What to prefer when choosing between several `alternate` links.
+///
+/// The default prefers `text/html` in any language.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkPreferences {
+    /// A media type such as `text/html`, compared ignoring case and parameters
+    pub mediatype: Option<String>,
+    /// A language tag such as `en`, which also matches more specific tags like `en-GB`
+    pub hreflang: Option<String>,
+}
+
+impl Default for LinkPreferences {
+    fn default() -> Self {
+        LinkPreferences {
+            mediatype: Some("text/html".to_string()),
+            hreflang: None,
+        }
+    }
+}
+
+impl LinkPreferences {
+    fn matches_mediatype(&self, link: &Link) -> bool {
+        match (self.mediatype.as_ref(), link.mediatype.as_ref()) {
+            (Some(wanted), Some(mediatype)) => mediatype.is(wanted),
+            _ => false,
+        }
+    }
+
+    fn matches_hreflang(&self, link: &Link) -> bool {
+        match (self.hreflang.as_ref(), link.hreflang.as_ref()) {
+            (Some(wanted), Some(hreflang)) => {
+                let (wanted, hreflang) = (wanted.to_lowercase(), hreflang.to_lowercase());
+                hreflang == wanted || hreflang.starts_with(&format!("{}-", wanted))
+            }
+            _ => false,
+        }
+    }
+}
+
+
+/// The best `alternate` link for `prefs`, where a matching language counts for more than a
+/// matching media type, and ties go to the link that comes first.
+pub fn alternate<'a>(links: &'a [Link], prefs: &LinkPreferences) -> Option<&'a Link> {
+    let mut best: Option<(&Link, u8)> = None;
+
+    for link in links.iter().filter(|link| link.has_rel(&LinkRel::Alternate)) {
+        let score = 2 * prefs.matches_hreflang(link) as u8 + prefs.matches_mediatype(link) as u8;
+
+        let is_better = match best {
+            Some((_, best_score)) => score > best_score,
+            None => true,
+        };
+
+        if is_better {
+            best = Some((link, score));
+        }
+    }
+
+    best.map(|(link, _)| link)
+}
+
+
+/// The prefix that turns a registered relation name into an IRI, which Atom treats as equivalent
+/// to the bare name.
+const IANA_RELATION_PREFIX: &str = "http://www.iana.org/assignments/relation/";
+
+
+/// The relation of a link to the feed or entry it appears in.
+///
+/// Registered relation names are matched case-insensitively, and are also recognized in their
+/// IRI form (`http://www.iana.org/assignments/relation/alternate`). Anything else is kept exactly
+/// as written in `Other`.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::LinkRel;
+///
+/// assert_eq!(LinkRel::from("edit-media"), LinkRel::EditMedia);
+/// assert_eq!(LinkRel::from("http://www.iana.org/assignments/relation/self"), LinkRel::Self_);
+/// assert_eq!(LinkRel::Previous.to_string(), "previous");
+/// assert_eq!(LinkRel::from("http://opds-spec.org/acquisition").to_string(),
+///            "http://opds-spec.org/acquisition");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LinkRel {
+    /// `alternate`, which is also the meaning of a link with no `rel`
+    Alternate,
+    Related,
+    /// `self`
+    Self_,
+    Enclosure,
+    Via,
+    Replies,
+    Edit,
+    EditMedia,
+    License,
+    Hub,
+    First,
+    Last,
+    /// `previous`, or its synonym `prev`
+    Previous,
+    Next,
+    Current,
+    PrevArchive,
+    NextArchive,
+    Other(String),
+}
+
+impl LinkRel {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            LinkRel::Alternate => "alternate",
+            LinkRel::Related => "related",
+            LinkRel::Self_ => "self",
+            LinkRel::Enclosure => "enclosure",
+            LinkRel::Via => "via",
+            LinkRel::Replies => "replies",
+            LinkRel::Edit => "edit",
+            LinkRel::EditMedia => "edit-media",
+            LinkRel::License => "license",
+            LinkRel::Hub => "hub",
+            LinkRel::First => "first",
+            LinkRel::Last => "last",
+            LinkRel::Previous => "previous",
+            LinkRel::Next => "next",
+            LinkRel::Current => "current",
+            LinkRel::PrevArchive => "prev-archive",
+            LinkRel::NextArchive => "next-archive",
+            LinkRel::Other(ref rel) => rel,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for LinkRel {
+    fn from(rel: &'a str) -> Self {
+        let name = rel.trim().to_lowercase();
+        let name = name.strip_prefix(IANA_RELATION_PREFIX).unwrap_or(&name);
+
+        match name {
+            "alternate" => LinkRel::Alternate,
+            "related" => LinkRel::Related,
+            "self" => LinkRel::Self_,
+            "enclosure" => LinkRel::Enclosure,
+            "via" => LinkRel::Via,
+            "replies" => LinkRel::Replies,
+            "edit" => LinkRel::Edit,
+            "edit-media" => LinkRel::EditMedia,
+            "license" => LinkRel::License,
+            "hub" => LinkRel::Hub,
+            "first" => LinkRel::First,
+            "last" => LinkRel::Last,
+            "previous" | "prev" => LinkRel::Previous,
+            "next" => LinkRel::Next,
+            "current" => LinkRel::Current,
+            "prev-archive" => LinkRel::PrevArchive,
+            "next-archive" => LinkRel::NextArchive,
+            _ => LinkRel::Other(rel.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for LinkRel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+
+/// A MIME media type, such as `text/html; charset=utf-8`.
+///
+/// The type is kept exactly as written, and parsed as needed by the accessors.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::MediaType;
+///
+/// let mediatype = MediaType::from("Text/HTML; charset=\"UTF-8\"");
+///
+/// assert_eq!(mediatype.essence(), "text/html");
+/// assert_eq!(mediatype.parameter("charset"), Some("UTF-8"));
+/// assert!(mediatype.is("text/html"));
+/// assert_eq!(mediatype.to_string(), "Text/HTML; charset=\"UTF-8\"");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MediaType(String);
+
+impl MediaType {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The type, before the `/`, as written.
+    pub fn main_type(&self) -> &str {
+        self.essence_str().split('/').next().unwrap_or("").trim()
+    }
+
+    /// The subtype, after the `/`, as written, or an empty string if there is none.
+    pub fn subtype(&self) -> &str {
+        self.essence_str().split('/').nth(1).unwrap_or("").trim()
+    }
+
+    /// The type and subtype without parameters, lowercased.
+    pub fn essence(&self) -> String {
+        format!("{}/{}", self.main_type(), self.subtype()).to_lowercase()
+    }
+
+    /// Whether the essence of this type is `essence`, ignoring case.
+    pub fn is(&self, essence: &str) -> bool {
+        self.essence() == essence.to_lowercase()
+    }
+
+    /// The value of a parameter, without any quotes around it. Parameter names are matched
+    /// case-insensitively.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.0.split(';').skip(1).find_map(|param| {
+            let i = param.find('=')?;
+            if param[..i].trim().eq_ignore_ascii_case(name) {
+                Some(param[i + 1..].trim().trim_matches('"'))
+            } else {
+                None
+            }
         })
     }
+
+    fn essence_str(&self) -> &str {
+        self.0.split(';').next().unwrap_or("")
+    }
+}
+
+impl<'a> From<&'a str> for MediaType {
+    fn from(mediatype: &'a str) -> Self {
+        MediaType(mediatype.to_string())
+    }
+}
+
+impl From<String> for MediaType {
+    fn from(mediatype: String) -> Self {
+        MediaType(mediatype)
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+
+/// The `length` of a link, which should be a number of bytes.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::Length;
+///
+/// assert_eq!(Length::from("1024").bytes(), Some(1024));
+/// assert_eq!(Length::from("unknown"), Length::Other(String::from("unknown")));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Length {
+    Bytes(u64),
+    /// A value that isn't a number of bytes, kept as written
+    Other(String),
+}
+
+impl Length {
+    pub fn bytes(&self) -> Option<u64> {
+        match *self {
+            Length::Bytes(n) => Some(n),
+            Length::Other(_) => None,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Length {
+    fn from(length: &'a str) -> Self {
+        // Only canonical numbers are parsed, so that values like "0042" are written back unchanged
+        match length.parse::<u64>() {
+            Ok(n) if n.to_string() == length => Length::Bytes(n),
+            _ => Length::Other(length.to_string()),
+        }
+    }
+}
+
+impl From<u64> for Length {
+    fn from(bytes: u64) -> Self {
+        Length::Bytes(bytes)
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Length::Bytes(n) => write!(f, "{}", n),
+            Length::Other(ref s) => f.write_str(s),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{Length, Link, LinkPreferences, LinkRel, MediaType};
+    use ::{Entry, Feed};
+
+    #[test]
+    fn test_round_trip() {
+        let atom_str = r#"
+            <feed xmlns="http://www.w3.org/2005/Atom">
+                <id></id>
+                <title></title>
+                <updated></updated>
+                <link href="http://test.blog/" rel="alternate" type="text/html"/>
+                <link href="http://test.blog/1.mp3" rel="enclosure" type="audio/mpeg" length="1024"/>
+                <link href="http://test.blog/2.mp3" rel="enclosure" type="audio/mpeg" length=" 12 MB"/>
+                <link href="http://test.blog/books" rel="http://opds-spec.org/acquisition" type="application/atom+xml;profile=opds-catalog;kind=acquisition"/>
+            </feed>"#;
+
+        let feed = Feed::from_str(atom_str).unwrap();
+        assert_eq!(feed.links[0].rel, Some(LinkRel::Alternate));
+        assert_eq!(feed.links[1].length, Some(Length::Bytes(1024)));
+        assert_eq!(feed.links[2].length, Some(Length::Other(" 12 MB".to_string())));
+        assert_eq!(feed.links[3].rel, Some(LinkRel::Other("http://opds-spec.org/acquisition".to_string())));
+
+        let mediatype = feed.links[3].mediatype.as_ref().unwrap();
+        assert!(mediatype.is("application/atom+xml"));
+        assert_eq!(mediatype.parameter("kind"), Some("acquisition"));
+
+        let atom_string = feed.to_string();
+        assert!(atom_string.contains("length=' 12 MB'"));
+        assert!(atom_string.contains("rel='http://opds-spec.org/acquisition' \
+                                      type='application/atom+xml;profile=opds-catalog;kind=acquisition'"));
+
+        let reread = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(reread.links, feed.links);
+    }
+
+    #[test]
+    fn test_link_helpers() {
+        let link = |href: &str, rel: Option<LinkRel>| Link { href: href.to_string(), rel, ..Default::default() };
+
+        let feed = Feed {
+            links: vec![
+                link("http://test.blog/feed.atom", Some(LinkRel::Self_)),
+                link("http://test.blog/", None),
+                link("https://hub1.example.com/", Some(LinkRel::Hub)),
+                link("https://hub2.example.com/", Some(LinkRel::Hub)),
+            ],
+            entries: vec![
+                Entry {
+                    links: vec![
+                        link("http://test.blog/1.mp3", Some(LinkRel::Enclosure)),
+                        link("http://test.blog/edit/1", Some(LinkRel::Edit)),
+                        link("http://test.blog/1.ogg", Some(LinkRel::Enclosure)),
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(feed.self_link().unwrap().href, "http://test.blog/feed.atom");
+        assert_eq!(feed.alternate_link(&LinkPreferences::default()).unwrap().href, "http://test.blog/");
+        assert_eq!(feed.hub_links().count(), 2);
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.enclosures().map(|e| e.url()).collect::<Vec<_>>(),
+                   vec!["http://test.blog/1.mp3", "http://test.blog/1.ogg"]);
+        assert_eq!(entry.edit_link().unwrap().href, "http://test.blog/edit/1");
+        assert!(entry.alternate_link(&LinkPreferences::default()).is_none());
+    }
+
+    #[test]
+    fn test_link_rel_case() {
+        assert_eq!(LinkRel::from("ALTERNATE"), LinkRel::Alternate);
+        assert_eq!(LinkRel::from("prev"), LinkRel::Previous);
+        assert_eq!(LinkRel::from("Custom"), LinkRel::Other("Custom".to_string()));
+        assert_eq!(MediaType::from("image/png").subtype(), "png");
+        assert_eq!(MediaType::from("nonsense").subtype(), "");
+    }
 }
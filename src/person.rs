@@ -1,8 +1,111 @@
+use xml::{Element, Xml};
+
+use ::{ElementUtils, NS, ResolveUris, XmlScope};
+use ::extension::ExtensionElement;
+
+
 /// [The Atom Syndication Format § Person Constructs]
 /// (https://tools.ietf.org/html/rfc4287#section-3.2)
-#[derive(Clone,Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Person {
     pub name: String,
     pub uri: Option<String>,
     pub email: Option<String>,
+    /// Child elements from other namespaces, such as FOAF or Portable Contacts profile data
+    pub extensions: Vec<ExtensionElement>,
+    /// The effective `xml:base` of the person, which `uri` may be relative to
+    pub base: Option<String>,
+}
+
+
+impl ResolveUris for Person {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+        ::resolve_optional(&mut self.uri, &base);
+    }
+}
+
+
+/// Writes a person as the Atom element `name`, which is `author` or `contributor`.
+pub fn to_xml(person: &Person, name: &str) -> Element {
+    let mut elem = Element::new(name.to_string(), Some(NS.to_string()), vec![]);
+
+    elem.tag_with_text("name", &person.name);
+    elem.tag_with_optional_text("uri", &person.uri);
+    elem.tag_with_optional_text("email", &person.email);
+
+    for extension in &person.extensions {
+        elem.tag(extension.to_xml());
+    }
+
+    elem.xml_attribute_with_optional_text("base", &person.base);
+
+    elem
+}
+
+
+/// Reads a person from an `author` or `contributor` element, failing with `missing_name` when it
+/// has no name.
+pub fn from_xml(elem: &Element, parent: &XmlScope, missing_name: &'static str) -> Result<Person, &'static str> {
+    let name = match elem.get_child("name", Some(NS)) {
+        Some(elem) => elem.content_str(),
+        None => return Err(missing_name),
+    };
+
+    let uri = elem.get_child("uri", Some(NS)).map(Element::content_str);
+    let email = elem.get_child("email", Some(NS)).map(Element::content_str);
+    let base = parent.of(elem).base;
+
+    // Children without a namespace are dropped, as they would be written back in the Atom namespace
+    let extensions = elem.children.iter()
+        .filter_map(|node| match *node {
+            Xml::ElementNode(ref child) if child.ns.is_some() && child.ns.as_ref().map(|ns| &ns[..]) != Some(NS) => {
+                Some(ExtensionElement::from_xml(child))
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(Person {
+        name,
+        uri,
+        email,
+        extensions,
+        base,
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+    use ::Feed;
+
+    #[test]
+    fn test_extensions_round_trip() {
+        let xml = r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:foaf="http://xmlns.com/foaf/0.1/" xml:lang="en">
+            <id>urn:test</id>
+            <title>Test</title>
+            <updated>2024-01-01T00:00:00Z</updated>
+            <author>
+                <name>N. Blogger</name>
+                <foaf:Person foaf:nick="blogger">
+                    <foaf:homepage rdf:resource="http://test.blog/" xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"/>
+                </foaf:Person>
+                <nick xmlns="">blogger</nick>
+            </author>
+        </feed>"#;
+
+        let feed = Feed::from_str(xml).unwrap();
+        assert_eq!(feed.authors[0].extensions.len(), 1);
+        let foaf = &feed.authors[0].extensions[0];
+        assert_eq!(foaf.name, "Person");
+        assert_eq!(foaf.ns.as_ref().map(|ns| &ns[..]), Some("http://xmlns.com/foaf/0.1/"));
+        assert_eq!(foaf.value, None);
+        assert_eq!(foaf.attributes.keys().collect::<Vec<_>>(), vec![&("nick".to_string(), Some("http://xmlns.com/foaf/0.1/".to_string()))]);
+        assert_eq!(foaf.child("homepage", Some("http://xmlns.com/foaf/0.1/")).unwrap().attributes.len(), 1);
+
+        let round_tripped = Feed::from_str(&feed.to_string()).unwrap();
+        assert_eq!(round_tripped.authors, feed.authors);
+    }
 }
@@ -0,0 +1,177 @@
+use xml::Element;
+
+use ::{Category, Entry, Feed, FeedFormat, Generator, Length, Link, LinkRel, MediaType, Person, Text};
+use ::date;
+
+
+pub const RSS_090_NS: &str = "http://my.netscape.com/rdf/simple/0.9/";
+pub const RSS_10_NS: &str = "http://purl.org/rss/1.0/";
+pub const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const CONTENT_NS: &str = "http://purl.org/rss/1.0/modules/content/";
+const DC_NS: &str = "http://purl.org/dc/elements/1.1/";
+
+
+/// Converts an `<rss>` (0.9x, 2.0) or `<rdf:RDF>` (0.90, 1.0) document into a `Feed`.
+pub fn from_rss(root: &Element, format: FeedFormat) -> Result<Feed, &'static str> {
+    let ns = match format {
+        FeedFormat::Rss090 => Some(RSS_090_NS),
+        FeedFormat::Rss10 => Some(RSS_10_NS),
+        _ => None,
+    };
+
+    let channel = match root.get_child("channel", ns) {
+        Some(channel) => channel,
+        None => return Err("RSS document is missing required <channel> element"),
+    };
+
+    // RDF-based versions put items and the image next to the channel instead of inside it.
+    let container = if ns.is_some() { root } else { channel };
+
+    let entries: Vec<Entry> = container.get_children("item", ns)
+        .map(|item| entry(item, ns))
+        .collect();
+
+    let title = text(channel, "title", ns).unwrap_or_default();
+    let link = text(channel, "link", ns);
+
+    let updated = match text(channel, "lastBuildDate", ns)
+        .or_else(|| text(channel, "pubDate", ns))
+        .or_else(|| text(channel, "date", Some(DC_NS))) {
+        Some(d) => date::normalize(&d),
+        None => entries.iter().map(|e| e.updated.clone()).fold(String::new(), date::latest),
+    };
+
+    let logo = container.get_child("image", ns).and_then(|image| text(image, "url", ns));
+
+    let generator = text(channel, "generator", ns).map(|name| Generator {
+        name,
+        ..Default::default()
+    });
+
+    let links = link.iter()
+        .map(|href| Link {
+            href: href.clone(),
+            rel: Some(LinkRel::Alternate),
+            ..Default::default()
+        })
+        .collect();
+
+    let authors = text(channel, "managingEditor", ns)
+        .or_else(|| text(channel, "creator", Some(DC_NS)))
+        .map(|s| vec![person(&s)])
+        .unwrap_or_default();
+
+    Ok(Feed {
+        id: link.unwrap_or_else(|| title.clone()),
+        title: title.into(),
+        updated,
+        logo,
+        rights: text(channel, "copyright", ns)
+            .or_else(|| text(channel, "rights", Some(DC_NS)))
+            .map(Text::from),
+        subtitle: text(channel, "description", ns).map(Text::from),
+        generator,
+        links,
+        categories: categories(channel, ns),
+        authors,
+        entries,
+        lang: text(channel, "language", ns).or_else(|| text(channel, "language", Some(DC_NS))),
+        ..Default::default()
+    })
+}
+
+
+fn entry(item: &Element, ns: Option<&str>) -> Entry {
+    let title = text(item, "title", ns);
+    let link = text(item, "link", ns);
+    let guid = text(item, "guid", ns)
+        .or_else(|| item.get_attribute("about", Some(RDF_NS)).map(String::from));
+
+    let published = text(item, "pubDate", ns)
+        .or_else(|| text(item, "date", Some(DC_NS)))
+        .map(|d| date::normalize(&d));
+
+    let mut links: Vec<Link> = link.iter()
+        .map(|href| Link {
+            href: href.clone(),
+            rel: Some(LinkRel::Alternate),
+            ..Default::default()
+        })
+        .collect();
+
+    links.extend(item.get_children("enclosure", ns).filter_map(|e| {
+        e.get_attribute("url", None).map(|url| Link {
+            href: url.to_string(),
+            rel: Some(LinkRel::Enclosure),
+            mediatype: e.get_attribute("type", None).map(MediaType::from),
+            length: e.get_attribute("length", None).map(Length::from),
+            ..Default::default()
+        })
+    }));
+
+    let authors = text(item, "author", ns)
+        .or_else(|| text(item, "creator", Some(DC_NS)))
+        .map(|s| vec![person(&s)])
+        .unwrap_or_default();
+
+    Entry {
+        id: guid.or_else(|| link.clone()).or_else(|| title.clone()).unwrap_or_default(),
+        title: title.unwrap_or_default().into(),
+        updated: published.clone().unwrap_or_default(),
+        published,
+        links,
+        categories: categories(item, ns),
+        authors,
+        summary: text(item, "description", ns).map(Text::html),
+        content: text(item, "encoded", Some(CONTENT_NS)).map(Text::html),
+        ..Default::default()
+    }
+}
+
+
+fn categories(elem: &Element, ns: Option<&str>) -> Vec<Category> {
+    elem.get_children("category", ns)
+        .map(|c| Category {
+            term: c.content_str().trim().to_string(),
+            scheme: c.get_attribute("domain", None).map(String::from),
+            ..Default::default()
+        })
+        .chain(elem.get_children("subject", Some(DC_NS)).map(|c| Category {
+            term: c.content_str().trim().to_string(),
+            ..Default::default()
+        }))
+        .filter(|c| !c.term.is_empty())
+        .collect()
+}
+
+
+/// RSS people are written as `"jane@example.com (Jane Doe)"`, or just one of the two.
+fn person(s: &str) -> Person {
+    let s = s.trim();
+
+    if let (Some(open), true) = (s.find('('), s.ends_with(')')) {
+        let email = s[..open].trim();
+        let name = s[open + 1..s.len() - 1].trim();
+
+        if !name.is_empty() {
+            return Person {
+                name: name.to_string(),
+                email: if email.is_empty() { None } else { Some(email.to_string()) },
+                ..Default::default()
+            };
+        }
+    }
+
+    Person {
+        name: s.to_string(),
+        email: if s.contains('@') && !s.contains(' ') { Some(s.to_string()) } else { None },
+        ..Default::default()
+    }
+}
+
+
+fn text(elem: &Element, name: &str, ns: Option<&str>) -> Option<String> {
+    elem.get_child(name, ns)
+        .map(|e| e.content_str().trim().to_string())
+        .filter(|s| !s.is_empty())
+}
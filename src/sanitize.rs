@@ -0,0 +1,646 @@
+//! Makes HTML from untrusted feeds safe to render, by keeping only the elements, attributes and
+//! URL schemes that a `Policy` allows.
+//!
+//! Everything else is removed: scripts and styles with their contents, event handler attributes,
+//! `javascript:` and other unlisted URLs, and any element the policy doesn't name, whose text is
+//! kept. The output is always well formed, with every element closed and every value escaped.
+//!
+//! The values of `html` and `xhtml` text are sanitized, while plain `text` is only escaped, so
+//! that markup in it is shown rather than read. Either way the result is HTML.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::{Entry, Text};
+//! use atom_syndication::sanitize::Policy;
+//!
+//! let entry = Entry {
+//!     content: Some(Text::html(r#"<p onclick="steal()">Hi <a href="../about">me</a><script>steal()</script></p>"#)),
+//!     base: Some(String::from("http://test.blog/posts/")),
+//!     ..Default::default()
+//! };
+//!
+//! let content = Policy::reader_view().content(&entry).unwrap();
+//! assert_eq!(content, r#"<p>Hi <a href="http://test.blog/about">me</a></p>"#);
+//!
+//! let preview = Policy::plain_preview().content(&entry).unwrap();
+//! assert_eq!(preview, "Hi me");
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ::{Entry, Text, TextType};
+use ::uri;
+
+
+/// Elements that never have content or an end tag.
+pub(crate) const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Elements that are part of a line of text, so removing them doesn't separate words.
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "del", "dfn", "em", "font", "i", "ins", "kbd",
+    "mark", "q", "s", "samp", "small", "span", "strike", "strong", "sub", "sup", "time", "tt", "u", "var",
+];
+
+/// Attributes whose values are URLs.
+const URL_ATTRIBUTES: &[&str] = &["action", "background", "cite", "formaction", "href", "longdesc", "poster", "src"];
+
+
+/// Which parts of HTML to keep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Policy {
+    /// Lowercase names of the elements to keep
+    pub elements: BTreeSet<String>,
+    /// Lowercase names of the attributes to keep, by element name. Those under `"*"` are kept on
+    /// every element.
+    pub attributes: BTreeMap<String, BTreeSet<String>>,
+    /// Lowercase URL schemes allowed in attributes such as `href` and `src`. Attributes with any
+    /// other scheme are removed, while relative URLs are resolved against the base if there is one.
+    pub url_schemes: BTreeSet<String>,
+    /// Lowercase names of the elements to remove along with everything inside them
+    pub remove_content: BTreeSet<String>,
+    /// Whether to collapse runs of whitespace into one space, as is wanted when no elements are
+    /// kept. Removed elements that aren't inline are replaced with a space.
+    pub collapse_whitespace: bool,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::reader_view()
+    }
+}
+
+impl Policy {
+    /// Keeps the structure and formatting of an article, along with its links and images, but
+    /// nothing that runs code, loads frames or changes the page's styles.
+    pub fn reader_view() -> Policy {
+        let elements = [
+            "a", "abbr", "article", "aside", "b", "blockquote", "br", "caption", "cite", "code", "dd", "del",
+            "details", "div", "dl", "dt", "em", "figcaption", "figure", "footer", "h1", "h2", "h3", "h4", "h5",
+            "h6", "header", "hr", "i", "img", "ins", "kbd", "li", "mark", "ol", "p", "pre", "q", "s", "section",
+            "small", "span", "strong", "sub", "summary", "sup", "table", "tbody", "td", "tfoot", "th", "thead",
+            "time", "tr", "u", "ul",
+        ];
+
+        let attributes = [
+            ("*", &["title", "lang", "dir"][..]),
+            ("a", &["href"][..]),
+            ("abbr", &["title"][..]),
+            ("blockquote", &["cite"][..]),
+            ("del", &["cite", "datetime"][..]),
+            ("img", &["src", "alt", "width", "height"][..]),
+            ("ins", &["cite", "datetime"][..]),
+            ("ol", &["start", "reversed", "type"][..]),
+            ("q", &["cite"][..]),
+            ("td", &["colspan", "rowspan"][..]),
+            ("th", &["colspan", "rowspan", "scope"][..]),
+            ("time", &["datetime"][..]),
+        ];
+
+        Policy {
+            elements: set(&elements),
+            attributes: attributes.iter().map(|&(element, names)| (element.to_string(), set(names))).collect(),
+            url_schemes: set(&["http", "https", "mailto"]),
+            remove_content: default_remove_content(),
+            collapse_whitespace: false,
+        }
+    }
+
+    /// Keeps only the text, on one line, for previews in lists and notifications. The result is
+    /// still escaped for use in HTML.
+    pub fn plain_preview() -> Policy {
+        Policy {
+            elements: BTreeSet::new(),
+            attributes: BTreeMap::new(),
+            url_schemes: BTreeSet::new(),
+            remove_content: default_remove_content(),
+            collapse_whitespace: true,
+        }
+    }
+
+    /// Sanitizes `html`, resolving relative URLs against `base` when it is given.
+    pub fn sanitize(&self, html: &str, base: Option<&str>) -> String {
+        let mut sanitizer = Sanitizer { policy: self, base, out: String::new(), open: Vec::new() };
+        let mut rest = html;
+
+        while let Some(i) = rest.find('<') {
+            sanitizer.text(&rest[..i]);
+            rest = &rest[i..];
+
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            } else if let Some((tag, len)) = Tag::parse(rest) {
+                rest = &rest[len..];
+
+                if tag.end {
+                    sanitizer.end_tag(&tag.name);
+                } else if self.remove_content.contains(&tag.name) {
+                    rest = if tag.self_closing { rest } else { skip_past_end_tag(rest, &tag.name) };
+                    sanitizer.separate(&tag.name);
+                } else {
+                    sanitizer.start_tag(&tag);
+                }
+            } else {
+                sanitizer.text("<");
+                rest = &rest[1..];
+            }
+        }
+
+        sanitizer.text(rest);
+        sanitizer.finish()
+    }
+
+    /// Escapes `text` for use in HTML, collapsing whitespace if the policy does.
+    pub fn escape(&self, text: &str) -> String {
+        let mut sanitizer = Sanitizer { policy: self, base: None, out: String::new(), open: Vec::new() };
+
+        for c in text.chars() {
+            sanitizer.char(c);
+        }
+
+        sanitizer.finish()
+    }
+
+    /// Sanitizes the value of `text` if it is HTML or XHTML, or escapes it if it is plain text,
+    /// keeping its other fields. The result is always HTML.
+    pub fn sanitize_text(&self, text: &Text, base: Option<&str>) -> Text {
+        let value = match text.text_type {
+            TextType::Text => self.escape(&text.value),
+            TextType::Html | TextType::Xhtml => self.sanitize(&text.value, base),
+        };
+
+        Text {
+            value,
+            text_type: TextType::Html,
+            ..text.clone()
+        }
+    }
+
+    /// The entry's content, sanitized with relative URLs resolved against the entry's base.
+    pub fn content(&self, entry: &Entry) -> Option<Text> {
+        entry.content.as_ref().map(|content| self.sanitize_text(content, entry.base.as_ref().map(|b| &b[..])))
+    }
+
+    /// The entry's summary, sanitized with relative URLs resolved against the entry's base.
+    pub fn summary(&self, entry: &Entry) -> Option<Text> {
+        entry.summary.as_ref().map(|summary| self.sanitize_text(summary, entry.base.as_ref().map(|b| &b[..])))
+    }
+
+    fn allows_attribute(&self, element: &str, name: &str) -> bool {
+        // Event handlers are never safe, whatever the policy says
+        if name.starts_with("on") {
+            return false;
+        }
+
+        [element, "*"].iter().any(|key| self.attributes.get(*key).is_some_and(|names| names.contains(name)))
+    }
+
+    /// The URL to write, or `None` if its scheme isn't allowed.
+    fn url(&self, value: &str, base: Option<&str>) -> Option<String> {
+        // Browsers ignore these, so "java\nscript:" is still a javascript: URL
+        let value: String = value.trim_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|&c| c != '\t' && c != '\n' && c != '\r')
+            .collect();
+
+        let value = match (scheme(&value), base) {
+            (None, Some(base)) => uri::resolve(base, &value),
+            _ => value,
+        };
+
+        match scheme(&value) {
+            Some(scheme) if !self.url_schemes.contains(&scheme) => None,
+            _ => Some(value),
+        }
+    }
+}
+
+
+fn set(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+
+fn default_remove_content() -> BTreeSet<String> {
+    set(&[
+        "applet", "audio", "embed", "frame", "frameset", "head", "iframe", "math", "noembed", "noframes",
+        "noscript", "object", "script", "select", "style", "svg", "template", "textarea", "title", "video",
+    ])
+}
+
+
+/// The lowercase scheme of a URL, if it has one.
+fn scheme(url: &str) -> Option<String> {
+    let end = url.find(['/', '?', '#']).unwrap_or(url.len());
+
+    url[..end].find(':')
+        .map(|colon| &url[..colon])
+        .filter(|scheme| uri::is_scheme(scheme))
+        .map(|scheme| scheme.to_ascii_lowercase())
+}
+
+
+/// The rest of `html` after the end tag of the element `name`, or nothing if it isn't closed.
+fn skip_past_end_tag<'a>(html: &'a str, name: &str) -> &'a str {
+    let lower = html.to_ascii_lowercase();
+    let end_tag = format!("</{}", name);
+
+    let mut from = 0;
+    while let Some(i) = lower[from..].find(&end_tag) {
+        let start = from + i;
+        let after = start + end_tag.len();
+
+        // Make sure this is "</name>" and not "</namespace>"
+        match lower.as_bytes().get(after) {
+            Some(b) if b.is_ascii_alphanumeric() || *b == b'-' => from = after,
+            _ => return html[after..].find('>').map_or("", |gt| &html[after + gt + 1..]),
+        }
+    }
+
+    ""
+}
+
+
+struct Tag {
+    /// The lowercase element name
+    name: String,
+    end: bool,
+    self_closing: bool,
+    /// Lowercase attribute names with their values, entities decoded
+    attributes: Vec<(String, String)>,
+}
+
+impl Tag {
+    /// Reads the tag at the start of `html`, returning it with its length in bytes.
+    fn parse(html: &str) -> Option<(Tag, usize)> {
+        let bytes = html.as_bytes();
+        let end = bytes.get(1) == Some(&b'/');
+        let mut i = if end { 2 } else { 1 };
+
+        if !bytes.get(i)?.is_ascii_alphabetic() {
+            return None;
+        }
+
+        let name_start = i;
+        while i < bytes.len() && !is_tag_space(bytes[i]) && bytes[i] != b'/' && bytes[i] != b'>' {
+            i += 1;
+        }
+        let name = html[name_start..i].to_ascii_lowercase();
+
+        let mut attributes = Vec::new();
+        let mut self_closing = false;
+
+        loop {
+            while i < bytes.len() && (is_tag_space(bytes[i]) || bytes[i] == b'/') {
+                self_closing = bytes[i] == b'/';
+                i += 1;
+            }
+
+            match bytes.get(i) {
+                None => return None,
+                Some(&b'>') => return Some((Tag { name, end, self_closing, attributes }, i + 1)),
+                Some(_) => self_closing = false,
+            }
+
+            // The first character is part of the name even when it is "=", as browsers read it
+            let attr_start = i;
+            i += 1;
+            while i < bytes.len() && !is_tag_space(bytes[i]) && !b"/>=".contains(&bytes[i]) {
+                i += 1;
+            }
+            let attr_name = html[attr_start..i].to_ascii_lowercase();
+
+            while i < bytes.len() && is_tag_space(bytes[i]) {
+                i += 1;
+            }
+
+            let mut value = String::new();
+
+            if bytes.get(i) == Some(&b'=') {
+                i += 1;
+                while i < bytes.len() && is_tag_space(bytes[i]) {
+                    i += 1;
+                }
+
+                let value_start;
+                match bytes.get(i) {
+                    Some(&quote) if quote == b'"' || quote == b'\'' => {
+                        value_start = i + 1;
+                        i = value_start + html[value_start..].find(quote as char)?;
+                        value = decode_entities(&html[value_start..i]);
+                        i += 1;
+                    }
+                    _ => {
+                        value_start = i;
+                        while i < bytes.len() && !is_tag_space(bytes[i]) && bytes[i] != b'>' {
+                            i += 1;
+                        }
+                        value = decode_entities(&html[value_start..i]);
+                    }
+                }
+            }
+
+            if !attributes.iter().any(|(name, _)| *name == attr_name) {
+                attributes.push((attr_name, value));
+            }
+        }
+    }
+}
+
+
+fn is_tag_space(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\n' || b == b'\r' || b == b'\x0c'
+}
+
+
+struct Sanitizer<'a> {
+    policy: &'a Policy,
+    base: Option<&'a str>,
+    out: String,
+    /// The kept elements that are still open, innermost last
+    open: Vec<String>,
+}
+
+impl<'a> Sanitizer<'a> {
+    fn text(&mut self, html: &str) {
+        for c in decode_entities(html).chars() {
+            self.char(c);
+        }
+    }
+
+    fn char(&mut self, c: char) {
+        match c {
+            c if c.is_whitespace() && self.policy.collapse_whitespace => {
+                if !self.out.is_empty() && !self.out.ends_with(' ') {
+                    self.out.push(' ');
+                }
+            }
+            '&' => self.out.push_str("&amp;"),
+            '<' => self.out.push_str("&lt;"),
+            '>' => self.out.push_str("&gt;"),
+            c => self.out.push(c),
+        }
+    }
+
+    /// Keeps words on either side of a removed element apart when whitespace is being collapsed.
+    fn separate(&mut self, name: &str) {
+        if self.policy.collapse_whitespace && !INLINE_ELEMENTS.contains(&name) {
+            self.char(' ');
+        }
+    }
+
+    fn start_tag(&mut self, tag: &Tag) {
+        if !self.policy.elements.contains(&tag.name) {
+            self.separate(&tag.name);
+            return;
+        }
+
+        self.out.push('<');
+        self.out.push_str(&tag.name);
+
+        for (name, value) in &tag.attributes {
+            if !self.policy.allows_attribute(&tag.name, name) {
+                continue;
+            }
+
+            let value = if URL_ATTRIBUTES.contains(&&name[..]) {
+                match self.policy.url(value, self.base) {
+                    Some(url) => url,
+                    None => continue,
+                }
+            } else {
+                value.clone()
+            };
+
+            self.out.push(' ');
+            self.out.push_str(name);
+            self.out.push_str("=\"");
+            self.out.push_str(&escape_attribute(&value));
+            self.out.push('"');
+        }
+
+        self.out.push('>');
+
+        if !VOID_ELEMENTS.contains(&&tag.name[..]) {
+            self.open.push(tag.name.clone());
+        }
+    }
+
+    fn end_tag(&mut self, name: &str) {
+        if !self.open.iter().any(|open| open == name) {
+            self.separate(name);
+            return;
+        }
+
+        // Close any elements left open inside this one
+        while let Some(open) = self.open.pop() {
+            self.out.push_str("</");
+            self.out.push_str(&open);
+            self.out.push('>');
+
+            if open == name {
+                break;
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        while let Some(open) = self.open.pop() {
+            self.out.push_str("</");
+            self.out.push_str(&open);
+            self.out.push('>');
+        }
+
+        if self.policy.collapse_whitespace {
+            let trimmed = self.out.trim_end().len();
+            self.out.truncate(trimmed);
+        }
+
+        self.out
+    }
+}
+
+
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+
+/// Decodes character references. Unknown named references are left as they are.
+fn decode_entities(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        match decode_entity(&rest[1..]) {
+            Some((c, len)) => {
+                decoded.push(c);
+                rest = &rest[1 + len..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+
+/// Decodes the reference after a "&", returning the character and the length of the reference.
+fn decode_entity(s: &str) -> Option<(char, usize)> {
+    if let Some(number) = s.strip_prefix('#') {
+        let (digits, radix, prefix) = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => (hex, 16, 2),
+            None => (number, 10, 1),
+        };
+
+        let len = digits.find(|c: char| !c.is_digit(radix)).unwrap_or(digits.len());
+        let code = u32::from_str_radix(&digits[..len], radix).ok()?;
+        let c = ::std::char::from_u32(code).filter(|&c| c != '\0').unwrap_or('\u{fffd}');
+        let semicolon = digits[len..].starts_with(';') as usize;
+
+        return Some((c, prefix + len + semicolon));
+    }
+
+    let len = s.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(s.len());
+    if !s[len..].starts_with(';') {
+        return None;
+    }
+
+    let c = match &s[..len] {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "shy" => '\u{ad}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "times" => '×',
+        "middot" => '·',
+        "bull" => '•',
+        "hellip" => '…',
+        "ndash" => '–',
+        "mdash" => '—',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "euro" => '€',
+        "pound" => '£',
+        "yen" => '¥',
+        "cent" => '¢',
+        "sect" => '§',
+        "para" => '¶',
+        _ => return None,
+    };
+
+    Some((c, len + 1))
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::Policy;
+    use ::{Feed, TextType};
+
+    #[test]
+    fn test_reader_view() {
+        let policy = Policy::reader_view();
+        let base = Some("http://test.blog/posts/1");
+
+        let cases = [
+            ("<p>Hello <b>world</b></p>", "<p>Hello <b>world</b></p>"),
+            ("<P CLASS=x ONMOUSEOVER='steal()'>Hi</P>", "<p>Hi</p>"),
+            ("<script>document.write('<p>')</script>after", "after"),
+            ("<style>p { display: none }</style><p style='color:red'>x</p>", "<p>x</p>"),
+            ("<iframe src='http://evil.test/'></iframe><center>kept</center>", "kept"),
+            ("<a href='javascript:steal()'>x</a>", "<a>x</a>"),
+            ("<a href=' jav&#x61;&#10;script:steal()'>x</a>", "<a>x</a>"),
+            (
+                "<a href='2#comments' title='a \"quote\"'>x</a>",
+                r#"<a href="http://test.blog/posts/2#comments" title="a &quot;quote&quot;">x</a>"#,
+            ),
+            ("<img src=/logo.png alt=Logo onerror=steal()>", r#"<img src="http://test.blog/logo.png" alt="Logo">"#),
+            ("<img src='data:image/svg+xml,...'>", "<img>"),
+            ("<ul><li>one<li>two</ul></p>", "<ul><li>one<li>two</li></li></ul>"),
+            ("<em>unclosed", "<em>unclosed</em>"),
+            ("1 < 2 &amp; 3 > 2 &copy; &bogus; <!-- comment -->", "1 &lt; 2 &amp; 3 &gt; 2 © &amp;bogus; "),
+            ("<scripts>not a script</scripts>", "not a script"),
+            ("<script>unclosed", ""),
+        ];
+
+        for &(html, expected) in &cases {
+            assert_eq!(policy.sanitize(html, base), expected, "{}", html);
+        }
+    }
+
+    #[test]
+    fn test_plain_preview() {
+        let policy = Policy::plain_preview();
+
+        let html = "<h1>Title</h1>\n<p>First   <em>para</em>graph.</p>\
+            <p>Second &amp; last<br>line</p><script>x</script>";
+        assert_eq!(policy.sanitize(html, None), "Title First paragraph. Second &amp; last line");
+    }
+
+    #[test]
+    fn test_text_types() {
+        let atom_str = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+            <id>urn:feed</id><title>Test</title><updated>2017-06-01T00:00:00Z</updated>
+            <entry xml:base="http://test.blog/posts/">
+                <id>urn:1</id><title>One</title><updated>2017-06-01T00:00:00Z</updated>
+                <content type="text">Use &lt;b&gt; for bold</content>
+                <summary type="html">&lt;b onclick="steal()"&gt;Bold&lt;/b&gt;&lt;script&gt;steal()&lt;/script&gt;</summary>
+            </entry>
+            <entry xml:base="http://test.blog/posts/">
+                <id>urn:2</id><title>Two</title><updated>2017-06-01T00:00:00Z</updated>
+                <content type="xhtml">
+                    <div xmlns="http://www.w3.org/1999/xhtml"><p>See <a href="2" onclick="steal()">this</a></p><br/></div>
+                </content>
+            </entry>
+        </feed>"#;
+
+        let feed = Feed::from_str(atom_str).unwrap();
+        let policy = Policy::reader_view();
+
+        let content = policy.content(&feed.entries[0]).unwrap();
+        assert_eq!(content, "Use &lt;b&gt; for bold");
+        assert_eq!(content.text_type, TextType::Html);
+        assert_eq!(policy.summary(&feed.entries[0]).unwrap(), "<b>Bold</b>");
+
+        assert_eq!(feed.entries[1].content.as_ref().unwrap().text_type, TextType::Xhtml);
+        assert_eq!(policy.content(&feed.entries[1]).unwrap(),
+                   r#"<p>See <a href="http://test.blog/posts/2">this</a></p><br>"#);
+        assert_eq!(Policy::plain_preview().content(&feed.entries[1]).unwrap(), "See this");
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let mut policy = Policy::reader_view();
+        policy.url_schemes.insert("magnet".to_string());
+        policy.attributes.get_mut("*").unwrap().insert("onclick".to_string());
+
+        assert_eq!(
+            policy.sanitize("<a href='magnet:?xt=urn:btih:abc' onclick='steal()'>x</a>", None),
+            r#"<a href="magnet:?xt=urn:btih:abc">x</a>"#
+        );
+    }
+}
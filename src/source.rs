@@ -1,24 +1,53 @@
 use xml::Element;
 
-use ::{Author, Category, Contributor, ElementUtils, Generator, Link, NS, Person, ViaXml};
+use ::{Author, Category, Contributor, ElementUtils, Feed, FromScopedXml, Generator, Link, NS, Person, ResolveUris, Text, ViaXml, XmlScope};
+use ::text;
 
 
 /// [The Atom Syndication Format § The "atom:source" Element]
 /// (https://tools.ietf.org/html/rfc4287#section-4.2.11)
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct Source {
     pub id: Option<String>,
-    pub title: Option<String>,
+    pub title: Option<Text>,
     pub updated: Option<String>,
     pub icon: Option<String>,
     pub logo: Option<String>,
-    pub rights: Option<String>,
-    pub subtitle: Option<String>,
+    pub rights: Option<Text>,
+    pub subtitle: Option<Text>,
     pub generator: Option<Generator>,
     pub links: Vec<Link>,
     pub categories: Vec<Category>,
     pub authors: Vec<Person>,
     pub contributors: Vec<Person>,
+    /// The effective `xml:base` of the source, which IRIs inside it may be relative to
+    pub base: Option<String>,
+}
+
+
+impl Source {
+    /// Describes `feed` as the origin of entries copied out of it.
+    ///
+    /// This keeps the feed metadata that [RFC 4287 § 4.2.11]
+    /// (https://tools.ietf.org/html/rfc4287#section-4.2.11) suggests, including the authors that
+    /// copied entries without their own inherit through the source, but not the feed's entries.
+    pub fn from_feed(feed: &Feed) -> Source {
+        Source {
+            id: Some(feed.id.clone()),
+            title: Some(feed.title.clone()),
+            updated: Some(feed.updated.clone()),
+            icon: feed.icon.clone(),
+            logo: feed.logo.clone(),
+            rights: feed.rights.clone(),
+            subtitle: feed.subtitle.clone(),
+            generator: feed.generator.clone(),
+            links: feed.links.clone(),
+            categories: feed.categories.clone(),
+            authors: feed.authors.clone(),
+            contributors: feed.contributors.clone(),
+            base: feed.base.clone(),
+        }
+    }
 }
 
 impl ViaXml for Source {
@@ -26,12 +55,12 @@ impl ViaXml for Source {
         let mut elem = Element::new("source".to_string(), Some(NS.to_string()), vec![]);
 
         elem.tag_with_optional_text("id", &self.id);
-        elem.tag_with_optional_text("title", &self.title);
+        elem.tag_with_optional_text_construct("title", &self.title);
         elem.tag_with_optional_text("updated", &self.updated);
         elem.tag_with_optional_text("icon", &self.icon);
         elem.tag_with_optional_text("logo", &self.logo);
-        elem.tag_with_optional_text("rights", &self.rights);
-        elem.tag_with_optional_text("subtitle", &self.subtitle);
+        elem.tag_with_optional_text_construct("rights", &self.rights);
+        elem.tag_with_optional_text_construct("subtitle", &self.subtitle);
 
         if let Some(ref g) = self.generator {
             elem.tag(g.to_xml());
@@ -53,52 +82,84 @@ impl ViaXml for Source {
             elem.tag(Contributor(person.clone()).to_xml());
         }
 
+        elem.xml_attribute_with_optional_text("base", &self.base);
+
         elem
     }
 
     fn from_xml(elem: Element) -> Result<Self, &'static str> {
+        Source::from_scoped_xml(&elem, &XmlScope::default())
+    }
+}
+
+
+impl FromScopedXml for Source {
+    fn from_scoped_xml(elem: &Element, parent: &XmlScope) -> Result<Self, &'static str> {
+        let scope = parent.of(elem);
+
         let id = elem.get_child("id", Some(NS)).map(Element::content_str);
-        let title = elem.get_child("title", Some(NS)).map(Element::content_str);
+        let title = elem.get_child("title", Some(NS)).map(|e| text::from_xml(e, &scope));
         let updated = elem.get_child("updated", Some(NS)).map(Element::content_str);
         let icon = elem.get_child("icon", Some(NS)).map(Element::content_str);
         let logo = elem.get_child("logo", Some(NS)).map(Element::content_str);
-        let rights = elem.get_child("rights", Some(NS)).map(Element::content_str);
-        let subtitle = elem.get_child("subtitle", Some(NS)).map(Element::content_str);
-        let generator = elem.get_child("generator", Some(NS)).map(|e| ViaXml::from_xml(e.clone()).unwrap());
+        let rights = elem.get_child("rights", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let subtitle = elem.get_child("subtitle", Some(NS)).map(|e| text::from_xml(e, &scope));
+        let generator = elem.get_child("generator", Some(NS)).map(|e| FromScopedXml::from_scoped_xml(e, &scope)).transpose()?;
 
         let links = elem.get_children("link", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let categories = elem.get_children("category", Some(NS))
-            .map(|e| ViaXml::from_xml(e.clone()).unwrap())
-            .collect();
+            .map(|e| ViaXml::from_xml(e.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let authors = elem.get_children("author", Some(NS))
-            .map(|e| {
-                let Author(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Author(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
 
         let contributors = elem.get_children("contributor", Some(NS))
-            .map(|e| {
-                let Contributor(person) = ViaXml::from_xml(e.clone()).unwrap();
-                person
-            }).collect();
+            .map(|e| FromScopedXml::from_scoped_xml(e, &scope).map(|Contributor(person)| person))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let base = scope.base.clone();
 
         Ok(Source {
-            id: id,
-            title: title,
-            updated: updated,
-            icon: icon,
-            logo: logo,
-            rights: rights,
-            subtitle: subtitle,
-            generator: generator,
-            links: links,
-            categories: categories,
-            authors: authors,
-            contributors: contributors,
+            id,
+            title,
+            updated,
+            icon,
+            logo,
+            rights,
+            subtitle,
+            generator,
+            links,
+            categories,
+            authors,
+            contributors,
+            base,
         })
     }
 }
+
+
+impl ResolveUris for Source {
+    fn resolve_uris(&mut self, document_url: &str, parent_base: &str) {
+        let base = ::resolve_base(&mut self.base, document_url, parent_base);
+
+        ::resolve_optional(&mut self.icon, &base);
+        ::resolve_optional(&mut self.logo, &base);
+
+        if let Some(ref mut generator) = self.generator {
+            generator.resolve_uris(document_url, &base);
+        }
+
+        for link in &mut self.links {
+            link.resolve_uris(document_url, &base);
+        }
+
+        for person in self.authors.iter_mut().chain(self.contributors.iter_mut()) {
+            person.resolve_uris(document_url, &base);
+        }
+    }
+}
@@ -0,0 +1,285 @@
+//! Bookkeeping for feed pollers, kept between polls so that each poll only reports entries that
+//! are new or have changed.
+//!
+//! `PollState` holds what is known about one feed, and a `FeedState` stores it for many feeds by
+//! URL. `MemoryState` keeps them for the life of the process and `FileState` keeps them in a
+//! directory. Nothing here makes HTTP requests.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::state::{FeedState, MemoryState};
+//!
+//! let url = "http://test.blog/feed.atom";
+//! let mut states = MemoryState::default();
+//!
+//! let mut state = states.load(url).unwrap();
+//! // Send `state.conditional_headers()` with the request, and stop here on "304 Not Modified"
+//! let body = br#"<feed xmlns="http://www.w3.org/2005/Atom">
+//!     <id>urn:feed</id><title>Test</title><updated>2017-06-01T00:00:00Z</updated>
+//!     <entry><id>urn:1</id><title>First</title><updated>2017-06-01T00:00:00Z</updated></entry>
+//! </feed>"#;
+//!
+//! let feed = state.read(body, None).unwrap();
+//! assert_eq!(feed.entries.len(), 1);
+//! state.etag = Some(String::from("\"abc\""));
+//! states.save(url, &state).unwrap();
+//!
+//! let mut state = states.load(url).unwrap();
+//! assert_eq!(state.conditional_headers(), vec![("If-None-Match", String::from("\"abc\""))]);
+//! assert!(state.read(body, None).unwrap().entries.is_empty());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use serde_json::{self, Map, Value};
+use sha2::{Digest, Sha256};
+
+use ::Feed;
+use ::date;
+
+
+/// What a poller knows about one feed from its previous polls.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PollState {
+    /// The `ETag` header of the last response
+    pub etag: Option<String>,
+    /// The `Last-Modified` header of the last response
+    pub last_modified: Option<String>,
+    /// The `updated` of each entry in the feed when it was last read, by entry id
+    pub seen: BTreeMap<String, String>,
+}
+
+impl PollState {
+    /// The headers to send so that the server can answer "304 Not Modified" when the feed
+    /// hasn't changed since the last response.
+    pub fn conditional_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+
+        if let Some(ref etag) = self.etag {
+            headers.push(("If-None-Match", etag.clone()));
+        }
+
+        if let Some(ref last_modified) = self.last_modified {
+            headers.push(("If-Modified-Since", last_modified.clone()));
+        }
+
+        headers
+    }
+
+    /// Reads a feed as `Feed::from_bytes` does, keeping only its unseen or changed entries and
+    /// recording them as seen.
+    pub fn read(&mut self, bytes: &[u8], charset: Option<&str>) -> Result<Feed, &'static str> {
+        let (feed, _) = Feed::from_bytes(bytes, charset)?;
+        Ok(self.record(feed))
+    }
+
+    /// Keeps only the entries of `feed` that haven't been seen, or whose `updated` has moved
+    /// forward since they were, and records every entry of `feed` as seen.
+    ///
+    /// Only the entries in `feed` are remembered, so an entry that drops out of the feed and
+    /// later comes back is reported again. Entries without an id can't be told apart, so they are
+    /// never recorded and are reported on every poll.
+    pub fn record(&mut self, mut feed: Feed) -> Feed {
+        let seen: BTreeMap<String, String> = feed.entries.iter()
+            .filter(|entry| !entry.id.trim().is_empty())
+            .map(|entry| (entry.id.clone(), entry.updated.clone()))
+            .collect();
+
+        feed.entries.retain(|entry| match self.seen.get(&entry.id) {
+            Some(updated) => date::is_later(&entry.updated, updated),
+            None => true,
+        });
+
+        self.seen = seen;
+
+        feed
+    }
+
+    fn to_json(&self, url: &str) -> Value {
+        let mut obj = Map::new();
+
+        obj.insert("url".to_string(), Value::from(url));
+
+        let optional = [("etag", &self.etag), ("last_modified", &self.last_modified)];
+        for &(key, value) in &optional {
+            if let Some(ref value) = *value {
+                obj.insert(key.to_string(), Value::from(&value[..]));
+            }
+        }
+
+        let seen = self.seen.iter().map(|(id, updated)| (id.clone(), Value::from(&updated[..]))).collect();
+        obj.insert("seen".to_string(), Value::Object(seen));
+
+        Value::Object(obj)
+    }
+
+    fn from_json(value: &Value) -> Option<PollState> {
+        let obj = value.as_object()?;
+        let string = |key| obj.get(key).and_then(Value::as_str).map(String::from);
+
+        let seen = obj.get("seen")?.as_object()?.iter()
+            .filter_map(|(id, updated)| updated.as_str().map(|updated| (id.clone(), updated.to_string())))
+            .collect();
+
+        Some(PollState {
+            etag: string("etag"),
+            last_modified: string("last_modified"),
+            seen,
+        })
+    }
+}
+
+
+/// Stores the `PollState` of many feeds by URL.
+pub trait FeedState {
+    /// The state of the feed at `url`, which is empty if it hasn't been saved before.
+    fn load(&self, url: &str) -> io::Result<PollState>;
+
+    fn save(&mut self, url: &str, state: &PollState) -> io::Result<()>;
+}
+
+
+/// Keeps feed states in memory, so that they are lost when it is dropped.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryState {
+    states: HashMap<String, PollState>,
+}
+
+impl FeedState for MemoryState {
+    fn load(&self, url: &str) -> io::Result<PollState> {
+        Ok(self.states.get(url).cloned().unwrap_or_default())
+    }
+
+    fn save(&mut self, url: &str, state: &PollState) -> io::Result<()> {
+        self.states.insert(url.to_string(), state.clone());
+        Ok(())
+    }
+}
+
+
+/// Keeps each feed's state in a JSON file in a directory, named after a hash of the feed's URL.
+#[derive(Clone, Debug)]
+pub struct FileState {
+    dir: PathBuf,
+}
+
+impl FileState {
+    /// Stores states in `dir`, which is created when the first state is saved.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> FileState {
+        FileState { dir: dir.into() }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        let hash: String = Sha256::digest(url.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+        self.dir.join(format!("{}.json", hash))
+    }
+}
+
+impl FeedState for FileState {
+    fn load(&self, url: &str) -> io::Result<PollState> {
+        let bytes = match fs::read(self.path(url)) {
+            Ok(bytes) => bytes,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(PollState::default()),
+            Err(e) => return Err(e),
+        };
+
+        serde_json::from_slice(&bytes).ok()
+            .and_then(|value| PollState::from_json(&value))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "feed state file is not valid"))
+    }
+
+    fn save(&mut self, url: &str, state: &PollState) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        // Written to a temporary file first so that a crash can't leave a half-written state
+        let path = self.path(url);
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, state.to_json(url).to_string())?;
+        fs::rename(&temp, &path)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+    use super::{FeedState, FileState, MemoryState};
+
+    /// A temporary directory that is removed when dropped, even if the test panics.
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn feed(entries: &[(&str, &str)]) -> Vec<u8> {
+        let entries: String = entries.iter()
+            .map(|&(id, updated)| {
+                format!("<entry><id>{}</id><title>{}</title><updated>{}</updated></entry>", id, id, updated)
+            })
+            .collect();
+
+        format!(r#"<feed xmlns="http://www.w3.org/2005/Atom"><id>urn:feed</id><title>Test</title>
+            <updated>2017-06-03T00:00:00Z</updated>{}</feed>"#, entries).into_bytes()
+    }
+
+    fn poll<S: FeedState>(states: &mut S, url: &str, body: &[u8]) -> Vec<String> {
+        let mut state = states.load(url).unwrap();
+        let feed = state.read(body, None).unwrap();
+        state.last_modified = Some("Sat, 03 Jun 2017 00:00:00 GMT".to_string());
+        states.save(url, &state).unwrap();
+
+        feed.entries.into_iter().map(|entry| entry.id).collect()
+    }
+
+    fn check_polls<S: FeedState>(states: &mut S) {
+        let url = "http://test.blog/feed.atom";
+
+        let first = feed(&[("urn:1", "2017-06-01T00:00:00Z"), ("urn:2", "2017-06-01T00:00:00Z")]);
+        assert_eq!(poll(states, url, &first), vec!["urn:1", "urn:2"]);
+        assert!(poll(states, url, &first).is_empty());
+
+        let second = feed(&[
+            ("urn:1", "2017-06-01T00:00:00Z"),
+            ("urn:2", "2017-06-02T00:00:00Z"),
+            ("urn:3", "2017-06-03T00:00:00Z"),
+        ]);
+        assert_eq!(poll(states, url, &second), vec!["urn:2", "urn:3"]);
+
+        let state = states.load(url).unwrap();
+        assert_eq!(state.conditional_headers()[0].0, "If-Modified-Since");
+        assert_eq!(state.seen.len(), 3);
+
+        assert_eq!(poll(states, "http://other.blog/feed.atom", &first).len(), 2);
+
+        let url = "http://anonymous.blog/feed.atom";
+        let no_ids = feed(&[("", "2017-06-01T00:00:00Z"), ("", "2017-06-02T00:00:00Z")]);
+        assert_eq!(poll(states, url, &no_ids).len(), 2);
+        assert_eq!(poll(states, url, &no_ids).len(), 2);
+        assert!(states.load(url).unwrap().seen.is_empty());
+    }
+
+    #[test]
+    fn test_memory_state() {
+        check_polls(&mut MemoryState::default());
+    }
+
+    #[test]
+    fn test_file_state() {
+        let dir = TempDir(env::temp_dir().join(format!("atom_syndication-state-{}", process::id())));
+
+        check_polls(&mut FileState::new(&dir.0));
+
+        // A new store over the same directory sees the saved states
+        let state = FileState::new(&dir.0).load("http://test.blog/feed.atom").unwrap();
+        assert_eq!(state.seen.len(), 3);
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt;
+use std::str::FromStr;
+
+use xml::{self, Element, Xml};
+
+use ::{MediaType, NS, XHTML_NS, XML_NS, XmlScope};
+use ::sanitize::VOID_ELEMENTS;
+
+
+/// How the value of a text construct is to be read, from its `type` attribute.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextType {
+    /// Plain text, which must be escaped before being shown as HTML
+    #[default]
+    Text,
+    /// HTML markup
+    Html,
+    /// XHTML markup, the contents of the construct's `xhtml:div`
+    Xhtml,
+}
+
+
+/// [The Atom Syndication Format § Text Constructs]
+/// (https://tools.ietf.org/html/rfc4287#section-3.1)
+///
+/// Also used for the content of an entry.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{Text, TextType};
+///
+/// let title = Text {
+///     value: String::from("Mon premier billet"),
+///     lang: Some(String::from("fr")),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(title, "Mon premier billet");
+/// assert_eq!(Text::from("My first post").lang, None);
+/// assert_eq!(Text::html("<em>My</em> first post").text_type, TextType::Html);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Text {
+    /// The text, or for HTML and XHTML, the markup
+    pub value: String,
+    pub text_type: TextType,
+    /// The effective `xml:lang` of the text
+    pub lang: Option<String>,
+    /// For content only, the IRI of content that is stored elsewhere, in which case `value` is
+    /// empty
+    pub src: Option<String>,
+    /// For content only, the media type of content that isn't text, HTML or XHTML
+    pub mediatype: Option<MediaType>,
+}
+
+
+impl Text {
+    /// Text whose value is HTML markup.
+    pub fn html<S: Into<String>>(value: S) -> Text {
+        Text {
+            value: value.into(),
+            text_type: TextType::Html,
+            ..Default::default()
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+}
+
+
+impl<'a> From<&'a str> for Text {
+    fn from(value: &'a str) -> Self {
+        Text {
+            value: value.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<String> for Text {
+    fn from(value: String) -> Self {
+        Text {
+            value,
+            ..Default::default()
+        }
+    }
+}
+
+
+impl PartialEq<str> for Text {
+    fn eq(&self, other: &str) -> bool {
+        self.value == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Text {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.value == *other
+    }
+}
+
+impl PartialEq<Text> for &str {
+    fn eq(&self, other: &Text) -> bool {
+        *self == other.value
+    }
+}
+
+
+impl fmt::Display for Text {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+
+pub fn to_xml(name: &'static str, text: &Text) -> Element {
+    let mut elem = Element::new(name.to_string(), Some(NS.to_string()), vec![]);
+
+    if let Some(ref lang) = text.lang {
+        elem.set_attribute("lang".to_string(), Some(XML_NS.to_string()), lang.clone());
+    }
+
+    if let Some(ref src) = text.src {
+        elem.set_attribute("src".to_string(), None, src.clone());
+    }
+
+    // Markup that isn't well formed can't be written as XHTML, so it is written as HTML
+    let div = match text.text_type {
+        TextType::Xhtml => Element::from_str(&format!("<div xmlns='{}'>{}</div>", XHTML_NS, text.value)).ok(),
+        _ => None,
+    };
+
+    let text_type = match (text.text_type, &div) {
+        (TextType::Xhtml, &None) => TextType::Html,
+        (text_type, _) => text_type,
+    };
+
+    let type_attr = match (text.mediatype.as_ref(), text_type) {
+        (Some(mediatype), _) => Some(mediatype.to_string()),
+        (None, TextType::Text) => None,
+        (None, TextType::Html) => Some("html".to_string()),
+        (None, TextType::Xhtml) => Some("xhtml".to_string()),
+    };
+
+    if let Some(type_attr) = type_attr {
+        elem.set_attribute("type".to_string(), None, type_attr);
+    }
+
+    match div {
+        Some(mut div) => {
+            div.prefixes.insert(XHTML_NS.to_string(), "xhtml".to_string());
+            elem.tag(div);
+        }
+        None => {
+            elem.text(text.value.clone());
+        }
+    }
+
+    elem
+}
+
+
+pub fn from_xml(elem: &Element, parent: &XmlScope) -> Text {
+    let type_attr = elem.get_attribute("type", None).map(str::trim);
+
+    let text_type = match type_attr {
+        Some("html") => TextType::Html,
+        Some("xhtml") => TextType::Xhtml,
+        _ => TextType::Text,
+    };
+
+    let mediatype = type_attr
+        .filter(|t| !["text", "html", "xhtml"].contains(t))
+        .map(MediaType::from);
+
+    let value = match text_type {
+        TextType::Xhtml => {
+            let mut markup = String::new();
+            write_markup(elem.get_child("div", Some(XHTML_NS)).unwrap_or(elem), &mut markup);
+            markup
+        }
+        _ => elem.content_str(),
+    };
+
+    Text {
+        value,
+        text_type,
+        lang: parent.of(elem).lang(),
+        src: elem.get_attribute("src", None).map(String::from),
+        mediatype,
+    }
+}
+
+
+/// Writes the children of an XHTML element as markup, with elements and attributes by their local
+/// names, as they would be written in HTML.
+fn write_markup(elem: &Element, out: &mut String) {
+    for child in &elem.children {
+        match *child {
+            Xml::ElementNode(ref child) => {
+                out.push('<');
+                out.push_str(&child.name);
+
+                let mut attributes: Vec<(String, &String)> = child.attributes.iter()
+                    .filter_map(|((name, ns), value)| match ns.as_ref().map(|ns| &ns[..]) {
+                        Some(XML_NS) => Some((format!("xml:{}", name), value)),
+                        Some(_) => None,
+                        None if name == "xmlns" => None,
+                        None => Some((name.clone(), value)),
+                    })
+                    .collect();
+                attributes.sort();
+
+                for (name, value) in attributes {
+                    out.push_str(&format!(" {}=\"{}\"", name, xml::escape(value)));
+                }
+
+                if child.children.is_empty() && VOID_ELEMENTS.contains(&&child.name[..]) {
+                    out.push_str("/>");
+                } else {
+                    out.push('>');
+                    write_markup(child, out);
+                    out.push_str(&format!("</{}>", child.name));
+                }
+            }
+            Xml::CharacterNode(ref text) | Xml::CDATANode(ref text) => out.push_str(&xml::escape(text)),
+            Xml::CommentNode(_) | Xml::PINode(_) => {}
+        }
+    }
+}
@@ -0,0 +1,236 @@
+/// Resolves `reference` against `base` as described in [RFC 3986 § 5.2]
+/// (https://tools.ietf.org/html/rfc3986#section-5.2).
+///
+/// Unlike a URL library this also works when `base` is itself relative, which is what happens
+/// when nested `xml:base` attributes are combined before the document's own URI is known.
+pub fn resolve(base: &str, reference: &str) -> String {
+    let b = Parts::split(base);
+    let r = Parts::split(reference);
+
+    let target = if r.scheme.is_some() {
+        Parts { path: remove_dot_segments(r.path), ..r.with_path() }
+    } else if r.authority.is_some() {
+        Parts { scheme: b.scheme, path: remove_dot_segments(r.path), ..r.with_path() }
+    } else if r.path.is_empty() {
+        Parts {
+            scheme: b.scheme,
+            authority: b.authority,
+            path: b.path.to_string(),
+            query: r.query.or(b.query),
+            fragment: r.fragment,
+        }
+    } else {
+        let path = if r.path.starts_with('/') {
+            remove_dot_segments(r.path)
+        } else {
+            remove_dot_segments(&merge(&b, r.path))
+        };
+
+        Parts { scheme: b.scheme, authority: b.authority, path, ..r.with_path() }
+    };
+
+    target.join()
+}
+
+
+struct Parts<'a, P> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: P,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Parts<'a, &'a str> {
+    fn split(uri: &'a str) -> Self {
+        let (rest, fragment) = match uri.find('#') {
+            Some(i) => (&uri[..i], Some(&uri[i + 1..])),
+            None => (uri, None),
+        };
+
+        let (rest, query) = match rest.find('?') {
+            Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+            None => (rest, None),
+        };
+
+        let (scheme, rest) = match rest.find(':') {
+            Some(i) if is_scheme(&rest[..i]) => (Some(&rest[..i]), &rest[i + 1..]),
+            _ => (None, rest),
+        };
+
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => match rest.find('/') {
+                Some(i) => (Some(&rest[..i]), &rest[i..]),
+                None => (Some(rest), ""),
+            },
+            None => (None, rest),
+        };
+
+        Parts { scheme, authority, path, query, fragment }
+    }
+
+    fn with_path(&self) -> Parts<'a, String> {
+        Parts {
+            scheme: self.scheme,
+            authority: self.authority,
+            path: self.path.to_string(),
+            query: self.query,
+            fragment: self.fragment,
+        }
+    }
+}
+
+impl<'a, P: AsRef<str>> Parts<'a, P> {
+    fn join(&self) -> String {
+        let mut s = String::new();
+
+        if let Some(scheme) = self.scheme {
+            s.push_str(scheme);
+            s.push(':');
+        }
+
+        if let Some(authority) = self.authority {
+            s.push_str("//");
+            s.push_str(authority);
+        }
+
+        s.push_str(self.path.as_ref());
+
+        if let Some(query) = self.query {
+            s.push('?');
+            s.push_str(query);
+        }
+
+        if let Some(fragment) = self.fragment {
+            s.push('#');
+            s.push_str(fragment);
+        }
+
+        s
+    }
+}
+
+
+pub fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => (),
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+}
+
+
+fn merge(base: &Parts<&str>, path: &str) -> String {
+    if base.authority.is_some() && base.path.is_empty() {
+        return format!("/{}", path);
+    }
+
+    match base.path.rfind('/') {
+        Some(i) => format!("{}{}", &base.path[..i + 1], path),
+        None => path.to_string(),
+    }
+}
+
+
+fn remove_dot_segments(path: &str) -> String {
+    let absolute = path.starts_with('/');
+    let mut segments: Vec<&str> = Vec::new();
+
+    let parts: Vec<&str> = path.split('/').collect();
+    let last = parts.len() - 1;
+
+    for (i, segment) in parts.iter().enumerate() {
+        match *segment {
+            "." => {
+                if i == last {
+                    segments.push("");
+                }
+            }
+            ".." => {
+                // A relative path keeps the `..` segments that go above its start, as they still
+                // apply once it is resolved against a base
+                if absolute {
+                    if segments.len() > 1 {
+                        segments.pop();
+                    }
+                } else if segments.last().is_some_and(|last| *last != "..") {
+                    segments.pop();
+                } else {
+                    segments.push("..");
+                }
+                if i == last {
+                    segments.push("");
+                }
+            }
+            s => segments.push(s),
+        }
+    }
+
+    let joined = segments.join("/");
+
+    if absolute && !joined.starts_with('/') {
+        format!("/{}", joined)
+    } else {
+        joined
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::resolve;
+
+    // https://tools.ietf.org/html/rfc3986#section-5.4
+    #[test]
+    fn test_rfc3986_examples() {
+        let base = "http://a/b/c/d;p?q";
+
+        let examples = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            (";x", "http://a/b/c/;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+        ];
+
+        for &(reference, expected) in &examples {
+            assert_eq!(resolve(base, reference), expected, "resolving {:?}", reference);
+        }
+    }
+
+    #[test]
+    fn test_relative_base() {
+        assert_eq!(resolve("/blog/", "posts/"), "/blog/posts/");
+        assert_eq!(resolve("blog/", "posts/42"), "blog/posts/42");
+        assert_eq!(resolve("", "/posts/42"), "/posts/42");
+        assert_eq!(resolve("../", "posts/"), "../posts/");
+        assert_eq!(resolve("../../blog/", "../posts/42"), "../../posts/42");
+        assert_eq!(resolve("blog/", "../../posts/"), "../posts/");
+        assert_eq!(resolve("http://ex.com/a/feed.atom", &resolve("../posts/", "42")), "http://ex.com/posts/42");
+    }
+}
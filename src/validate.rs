@@ -0,0 +1,421 @@
+//! Checks for problems that don't stop a feed from being read, but that consumers care about.
+//!
+//! Each check returns every problem it finds rather than stopping at the first.
+
+use ::{Feed, Length, Person};
+use ::uri;
+
+
+/// Where in a feed a problem was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Location {
+    Feed,
+    /// The entry at this index of `Feed::entries`
+    Entry(usize),
+    /// The enclosure at the second index of `Entry::enclosures`, in the entry at the first
+    Enclosure(usize, usize),
+    /// The author at this index of `Feed::authors`
+    FeedAuthor(usize),
+    /// The contributor at this index of `Feed::contributors`
+    FeedContributor(usize),
+    /// The author at the second index of `Entry::authors`, in the entry at the first
+    EntryAuthor(usize, usize),
+    /// The contributor at the second index of `Entry::contributors`, in the entry at the first
+    EntryContributor(usize, usize),
+}
+
+
+/// A problem found by a check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Issue {
+    pub location: Location,
+    pub message: &'static str,
+}
+
+
+/// Checks that a podcast has the elements that podcast directories such as Apple Podcasts require
+/// before they will list it.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::Feed;
+/// use atom_syndication::validate::{self, Issue, Location};
+///
+/// let feed = Feed {
+///     title: "My Podcast".into(),
+///     ..Default::default()
+/// };
+///
+/// let issues = validate::podcast(&feed);
+/// assert!(issues.contains(&Issue {
+///     location: Location::Feed,
+///     message: "podcast is missing <itunes:image>",
+/// }));
+/// ```
+pub fn podcast(feed: &Feed) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let mut feed_issue = |message| issues.push(Issue { location: Location::Feed, message });
+
+    if feed.title.value.trim().is_empty() {
+        feed_issue("podcast is missing a title");
+    }
+
+    if feed.lang.is_none() {
+        feed_issue("podcast is missing a language (xml:lang)");
+    }
+
+    let itunes = feed.itunes.clone().unwrap_or_default();
+
+    if itunes.author.is_none() && feed.authors.is_empty() {
+        feed_issue("podcast is missing an author");
+    }
+
+    if itunes.image.is_none() {
+        feed_issue("podcast is missing <itunes:image>");
+    }
+
+    if itunes.categories.is_empty() {
+        feed_issue("podcast is missing <itunes:category>");
+    }
+
+    if itunes.explicit.is_none() {
+        feed_issue("podcast is missing <itunes:explicit>");
+    }
+
+    for (i, entry) in feed.entries.iter().enumerate() {
+        let mut entry_issue = |message| issues.push(Issue { location: Location::Entry(i), message });
+
+        if entry.title.value.trim().is_empty() {
+            entry_issue("episode is missing a title");
+        }
+
+        if entry.id.trim().is_empty() {
+            entry_issue("episode is missing an id");
+        }
+
+        match entry.enclosures().next() {
+            Some(enclosure) => {
+                if enclosure.mediatype.is_none() {
+                    entry_issue("episode enclosure is missing a type");
+                }
+                match enclosure.length {
+                    None => entry_issue("episode enclosure is missing a length"),
+                    Some(Length::Other(_)) => entry_issue("episode enclosure length is not a number of bytes"),
+                    Some(Length::Bytes(_)) => {}
+                }
+            }
+            None => entry_issue("episode is missing an enclosure link"),
+        }
+    }
+
+    issues
+}
+
+
+/// Checks that every enclosure says how big its file is and what type it is, which clients need
+/// to decide whether and how to download it.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{Entry, Feed, Length, Link, LinkRel};
+/// use atom_syndication::validate::{self, Issue, Location};
+///
+/// let feed = Feed {
+///     entries: vec![
+///         Entry {
+///             links: vec![
+///                 Link {
+///                     href: String::from("http://test.blog/1.mp3"),
+///                     rel: Some(LinkRel::Enclosure),
+///                     mediatype: Some("audio/mpeg".into()),
+///                     length: Some(Length::from("about 20MB")),
+///                     ..Default::default()
+///                 },
+///             ],
+///             ..Default::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(validate::enclosures(&feed), vec![Issue {
+///     location: Location::Enclosure(0, 0),
+///     message: "enclosure length is not a number of bytes",
+/// }]);
+/// ```
+pub fn enclosures(feed: &Feed) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for (i, entry) in feed.entries.iter().enumerate() {
+        for (j, enclosure) in entry.enclosures().enumerate() {
+            let mut issue = |message| issues.push(Issue { location: Location::Enclosure(i, j), message });
+
+            match enclosure.length {
+                None => issue("enclosure is missing a length"),
+                Some(Length::Other(_)) => issue("enclosure length is not a number of bytes"),
+                Some(Length::Bytes(_)) => {}
+            }
+
+            if enclosure.mediatype.is_none() {
+                issue("enclosure is missing a type");
+            }
+        }
+    }
+
+    issues
+}
+
+
+
+/// Checks that every author and contributor has a name, that their emails are addresses as
+/// [RFC 2822] defines them and that their URIs are IRI references as [RFC 3987] defines them.
+///
+/// Quoted local parts and comments in emails aren't accepted, as feed readers rarely handle them.
+///
+/// [RFC 2822]: https://tools.ietf.org/html/rfc2822#section-3.4.1
+/// [RFC 3987]: https://tools.ietf.org/html/rfc3987#section-2.2
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{Feed, Person};
+/// use atom_syndication::validate::{self, Issue, Location};
+///
+/// let feed = Feed {
+///     authors: vec![Person {
+///         name: String::from("N. Blogger"),
+///         email: Some(String::from("N. Blogger <blogger@test.blog>")),
+///         ..Default::default()
+///     }],
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(validate::people(&feed), vec![Issue {
+///     location: Location::FeedAuthor(0),
+///     message: "person email is not a valid address",
+/// }]);
+/// ```
+pub fn people(feed: &Feed) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    for (i, person) in feed.authors.iter().enumerate() {
+        check_person(person, Location::FeedAuthor(i), &mut issues);
+    }
+
+    for (i, person) in feed.contributors.iter().enumerate() {
+        check_person(person, Location::FeedContributor(i), &mut issues);
+    }
+
+    for (i, entry) in feed.entries.iter().enumerate() {
+        for (j, person) in entry.authors.iter().enumerate() {
+            check_person(person, Location::EntryAuthor(i, j), &mut issues);
+        }
+
+        for (j, person) in entry.contributors.iter().enumerate() {
+            check_person(person, Location::EntryContributor(i, j), &mut issues);
+        }
+    }
+
+    issues
+}
+
+
+fn check_person(person: &Person, location: Location, issues: &mut Vec<Issue>) {
+    let mut issue = |message| issues.push(Issue { location, message });
+
+    if person.name.trim().is_empty() {
+        issue("person name is empty");
+    }
+
+    if person.email.as_ref().is_some_and(|email| !is_email(email.trim())) {
+        issue("person email is not a valid address");
+    }
+
+    if person.uri.as_ref().is_some_and(|uri| !is_iri_reference(uri.trim())) {
+        issue("person uri is not a valid IRI reference");
+    }
+}
+
+
+fn is_email(s: &str) -> bool {
+    let (local, domain) = match s.rfind('@') {
+        Some(at) => (&s[..at], &s[at + 1..]),
+        None => return false,
+    };
+
+    let is_atom = |atom: &str| {
+        !atom.is_empty() && atom.chars().all(|c| {
+            c.is_ascii_alphanumeric() || !c.is_ascii() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+        })
+    };
+
+    let is_label = |label: &str| {
+        !label.is_empty() && !label.starts_with('-') && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || !c.is_ascii() || c == '-')
+    };
+
+    let is_domain_literal = domain.starts_with('[') && domain.ends_with(']') && domain.len() > 2
+        && domain[1..domain.len() - 1].chars().all(|c| c.is_ascii_graphic() && !"[]\\".contains(c));
+
+    local.split('.').all(is_atom) && (is_domain_literal || domain.split('.').all(is_label))
+}
+
+
+fn is_iri_reference(s: &str) -> bool {
+    if s.chars().any(|c| c.is_whitespace() || c.is_control() || "<>\"{}|\\^`".contains(c)) {
+        return false;
+    }
+
+    // Every "%" must start a percent-encoded octet
+    let bytes = s.as_bytes();
+    for (i, _) in s.match_indices('%') {
+        if bytes[i + 1..].iter().take(2).filter(|b| b.is_ascii_hexdigit()).count() != 2 {
+            return false;
+        }
+    }
+
+    // A colon before any "/", "?" or "#" ends a scheme, which must be well formed
+    let end = s.find(['/', '?', '#']).unwrap_or(s.len());
+    match s[..end].find(':') {
+        Some(colon) => uri::is_scheme(&s[..colon]),
+        None => true,
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::{enclosures, is_email, is_iri_reference, people, podcast, Issue, Location};
+    use ::extension::itunes::{ITunesCategory, ITunesFeedExtension};
+    use ::{Entry, Feed, Length, Link, LinkRel, Person};
+
+    #[test]
+    fn test_complete_podcast() {
+        let feed = Feed {
+            title: "My Podcast".into(),
+            lang: Some("en".to_string()),
+            itunes: Some(ITunesFeedExtension {
+                author: Some("N. Blogger".to_string()),
+                image: Some("http://test.blog/artwork.jpg".to_string()),
+                explicit: Some(false),
+                categories: vec![ITunesCategory { text: "Technology".to_string(), ..Default::default() }],
+                ..Default::default()
+            }),
+            entries: vec![
+                Entry {
+                    id: "urn:uuid:4ae8550b-2987-49fa-9f8c-54c180c418ac".to_string(),
+                    title: "Episode 1".into(),
+                    links: vec![
+                        Link {
+                            href: "http://test.blog/1.mp3".to_string(),
+                            rel: Some(LinkRel::Enclosure),
+                            mediatype: Some("audio/mpeg".into()),
+                            length: Some(Length::Bytes(1024)),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(podcast(&feed), vec![]);
+    }
+
+    #[test]
+    fn test_episode_without_enclosure() {
+        let feed = Feed {
+            entries: vec![Entry { title: "Episode 1".into(), ..Default::default() }],
+            ..Default::default()
+        };
+
+        let issues = podcast(&feed);
+        assert!(issues.contains(&Issue { location: Location::Feed, message: "podcast is missing a title" }));
+        assert!(issues.contains(&Issue { location: Location::Entry(0), message: "episode is missing an id" }));
+        assert!(issues.contains(&Issue {
+            location: Location::Entry(0),
+            message: "episode is missing an enclosure link",
+        }));
+    }
+
+    #[test]
+    fn test_enclosures() {
+        let feed = Feed {
+            entries: vec![
+                Entry::default(),
+                Entry {
+                    links: vec![
+                        Link { href: "http://test.blog/".to_string(), ..Default::default() },
+                        Link {
+                            href: "http://test.blog/2.mp3".to_string(),
+                            rel: Some(LinkRel::Enclosure),
+                            mediatype: Some("audio/mpeg".into()),
+                            length: Some(Length::Bytes(1024)),
+                            ..Default::default()
+                        },
+                        Link {
+                            href: "http://test.blog/2.ogg".to_string(),
+                            rel: Some(LinkRel::Enclosure),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(enclosures(&feed), vec![
+            Issue { location: Location::Enclosure(1, 1), message: "enclosure is missing a length" },
+            Issue { location: Location::Enclosure(1, 1), message: "enclosure is missing a type" },
+        ]);
+    }
+
+    #[test]
+    fn test_people() {
+        let person = |name: &str, email: Option<&str>, uri: Option<&str>| Person {
+            name: name.to_string(),
+            email: email.map(String::from),
+            uri: uri.map(String::from),
+            ..Default::default()
+        };
+
+        let feed = Feed {
+            authors: vec![person("N. Blogger", Some("blogger@test.blog"), Some("http://test.blog/"))],
+            entries: vec![
+                Entry {
+                    authors: vec![person(" ", None, Some("/about"))],
+                    contributors: vec![person("Guest", Some("guest at test.blog"), Some("http://test.blog/~guest me"))],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(people(&feed), vec![
+            Issue { location: Location::EntryAuthor(0, 0), message: "person name is empty" },
+            Issue { location: Location::EntryContributor(0, 0), message: "person email is not a valid address" },
+            Issue { location: Location::EntryContributor(0, 0), message: "person uri is not a valid IRI reference" },
+        ]);
+    }
+
+    #[test]
+    fn test_email_and_iri_syntax() {
+        for email in &["a@b", "first.last+tag@sub.test.blog", "o'neil@[192.0.2.1]", "jörg@bücher.example"] {
+            assert!(is_email(email), "{}", email);
+        }
+        for email in &["", "@test.blog", "a@", "a..b@test.blog", "a@-test.blog", "a@test..blog", "N <a@b>"] {
+            assert!(!is_email(email), "{}", email);
+        }
+
+        for iri in &["", "http://test.blog/~blogger", "../about#me", "mailto:a@b", "http://例え.jp/%E2%9C%93"] {
+            assert!(is_iri_reference(iri), "{}", iri);
+        }
+        for iri in &["http://test.blog/a b", "<http://test.blog/>", "http://test.blog/%zz", "1http://test.blog/"] {
+            assert!(!is_iri_reference(iri), "{}", iri);
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Helpers for subscribing to feeds with [WebSub](https://www.w3.org/TR/websub/), formerly known
+//! as PubSubHubbub.
+//!
+//! Nothing here makes HTTP requests. `Discovery` finds the hubs and topic URL to subscribe to,
+//! `SubscriptionRequest` builds the body to POST to a hub and answers the hub's verification
+//! request, and `parse_notification` checks the signature of a pushed feed before reading it.
+//!
+//! # Examples
+//!
+//! ```
+//! use atom_syndication::Feed;
+//! use atom_syndication::websub::{Discovery, SubscriptionRequest};
+//!
+//! let atom_str = r#"
+//! <feed xmlns="http://www.w3.org/2005/Atom">
+//!   <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>
+//!   <title>My Blog</title>
+//!   <updated>2019-04-01T07:30:00Z</updated>
+//!   <link rel="hub" href="https://hub.example.com/"/>
+//!   <link rel="self" href="https://test.blog/feed.atom"/>
+//! </feed>
+//! "#;
+//!
+//! let feed = atom_str.parse::<Feed>().unwrap();
+//! let discovery = Discovery::from_feed(&feed);
+//!
+//! let request = SubscriptionRequest {
+//!     hub: discovery.hubs[0].clone(),
+//!     topic: discovery.topic.unwrap(),
+//!     callback: String::from("https://reader.example.com/push/42"),
+//!     secret: Some(String::from("s3cr3t")),
+//!     ..Default::default()
+//! };
+//!
+//! assert_eq!(request.form_body(), "hub.mode=subscribe\
+//!     &hub.topic=https%3A%2F%2Ftest.blog%2Ffeed.atom\
+//!     &hub.callback=https%3A%2F%2Freader.example.com%2Fpush%2F42\
+//!     &hub.secret=s3cr3t");
+//! ```
+
+use hmac::{Mac, SimpleHmac};
+use hmac::digest::Digest;
+use hmac::digest::core_api::BlockSizeUser;
+use sha1::Sha1;
+use sha2::{Sha256, Sha384, Sha512};
+
+use ::Feed;
+
+
+/// The hubs and topic URL of a feed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Discovery {
+    /// The URLs of the hubs that publish updates to the feed
+    pub hubs: Vec<String>,
+    /// The canonical URL of the feed, which is the topic to subscribe to
+    pub topic: Option<String>,
+}
+
+impl Discovery {
+    /// Finds the `rel="hub"` and `rel="self"` links of a feed.
+    pub fn from_feed(feed: &Feed) -> Discovery {
+        Discovery {
+            hubs: feed.hub_links().map(|link| link.href.clone()).collect(),
+            topic: feed.self_link().map(|link| link.href.clone()),
+        }
+    }
+
+    /// Finds the `rel="hub"` and `rel="self"` links in the values of HTTP `Link` headers.
+    ///
+    /// Publishers should advertise the same links in the headers as in the feed, but the WebSub
+    /// specification says the headers take precedence when both are present.
+    pub fn from_link_headers<'a, I: IntoIterator<Item = &'a str>>(headers: I) -> Discovery {
+        let mut discovery = Discovery::default();
+
+        for value in headers.into_iter().flat_map(|header| split_unquoted(header, ',')) {
+            let mut parts = split_unquoted(value, ';').into_iter().map(str::trim);
+
+            let href = match parts.next() {
+                Some(href) if href.starts_with('<') && href.ends_with('>') => &href[1..href.len() - 1],
+                _ => continue,
+            };
+
+            let rels = parts
+                .filter_map(|param| {
+                    let (name, value) = param.split_at(param.find('=')?);
+                    if name.trim().eq_ignore_ascii_case("rel") {
+                        Some(value[1..].trim().trim_matches('"').to_string())
+                    } else {
+                        None
+                    }
+                })
+                .flat_map(|rels| rels.split_whitespace().map(str::to_lowercase).collect::<Vec<_>>());
+
+            for rel in rels {
+                match &rel[..] {
+                    "hub" => discovery.hubs.push(href.to_string()),
+                    "self" if discovery.topic.is_none() => discovery.topic = Some(href.to_string()),
+                    _ => {}
+                }
+            }
+        }
+
+        discovery
+    }
+}
+
+
+/// Whether a subscription request starts or ends a subscription.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Subscribe,
+    Unsubscribe,
+}
+
+impl Mode {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            Mode::Subscribe => "subscribe",
+            Mode::Unsubscribe => "unsubscribe",
+        }
+    }
+}
+
+
+/// A request to a hub to start or end a subscription, to be sent as an
+/// `application/x-www-form-urlencoded` POST to `hub`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SubscriptionRequest {
+    pub hub: String,
+    pub topic: String,
+    /// The URL the hub will verify the subscription with, and then deliver notifications to
+    pub callback: String,
+    pub mode: Mode,
+    /// How long the subscription should last, which the hub may change
+    pub lease_seconds: Option<u64>,
+    /// The key the hub signs notifications with; see `parse_notification`
+    pub secret: Option<String>,
+}
+
+impl SubscriptionRequest {
+    /// The body of the request.
+    pub fn form_body(&self) -> String {
+        let mut params = vec![
+            ("hub.mode", self.mode.as_str().to_string()),
+            ("hub.topic", self.topic.clone()),
+            ("hub.callback", self.callback.clone()),
+        ];
+
+        if let Some(lease_seconds) = self.lease_seconds {
+            params.push(("hub.lease_seconds", lease_seconds.to_string()));
+        }
+
+        if let Some(ref secret) = self.secret {
+            params.push(("hub.secret", secret.clone()));
+        }
+
+        params.iter()
+            .map(|&(name, ref value)| format!("{}={}", name, form_encode(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+
+    /// Checks the query string of the hub's verification request to the callback, returning the
+    /// challenge to echo back in the response body if it is for this request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom_syndication::websub::SubscriptionRequest;
+    ///
+    /// let request = SubscriptionRequest {
+    ///     hub: String::from("https://hub.example.com/"),
+    ///     topic: String::from("https://test.blog/feed.atom"),
+    ///     callback: String::from("https://reader.example.com/push/42"),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let query = "hub.mode=subscribe&hub.topic=https%3A%2F%2Ftest.blog%2Ffeed.atom\
+    ///     &hub.challenge=a1b2c3&hub.lease_seconds=86400";
+    /// assert_eq!(request.verify_intent(query), Some(String::from("a1b2c3")));
+    /// ```
+    pub fn verify_intent(&self, query: &str) -> Option<String> {
+        let mut mode = None;
+        let mut topic = None;
+        let mut challenge = None;
+
+        for pair in query.split('&') {
+            let mut parts = pair.splitn(2, '=');
+            let name = form_decode(parts.next()?)?;
+            let value = form_decode(parts.next().unwrap_or(""))?;
+
+            match &name[..] {
+                "hub.mode" => mode = Some(value),
+                "hub.topic" => topic = Some(value),
+                "hub.challenge" => challenge = Some(value),
+                _ => {}
+            }
+        }
+
+        if mode? == self.mode.as_str() && topic? == self.topic {
+            challenge
+        } else {
+            None
+        }
+    }
+}
+
+
+/// Reads a feed delivered to a subscription's callback, after checking that it was signed with
+/// the subscription's secret.
+///
+/// `signature` is the value of the `X-Hub-Signature` header, and `charset` is the `charset`
+/// parameter of the `Content-Type` header, as for `Feed::from_bytes`. Notifications that aren't
+/// signed, or aren't signed with `secret`, are rejected.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::websub;
+///
+/// let body = b"<feed xmlns='http://www.w3.org/2005/Atom'><id></id><title></title><updated></updated></feed>";
+/// let signature = "sha256=8d5f3b4b6ba1b1dd0d2b0ff3b2e85d4c3b5d0a7b7d1a0f2ad42c7f0e3e4a6f12";
+///
+/// assert_eq!(websub::parse_notification(body, Some(signature), "s3cr3t", None).err(),
+///            Some("X-Hub-Signature does not match the notification"));
+/// ```
+pub fn parse_notification(body: &[u8], signature: Option<&str>, secret: &str, charset: Option<&str>)
+    -> Result<Feed, &'static str>
+{
+    let signature = match signature {
+        Some(signature) => signature.trim(),
+        None => return Err("Notification is missing an X-Hub-Signature"),
+    };
+
+    let (method, expected) = match signature.find('=') {
+        Some(i) => (&signature[..i], &signature[i + 1..]),
+        None => return Err("X-Hub-Signature is not in the form method=signature"),
+    };
+
+    let key = secret.as_bytes();
+    let expected = from_hex(expected).unwrap_or_default();
+    let matches = match &method.to_lowercase()[..] {
+        "sha1" => verify_hmac::<Sha1>(key, body, &expected),
+        "sha256" => verify_hmac::<Sha256>(key, body, &expected),
+        "sha384" => verify_hmac::<Sha384>(key, body, &expected),
+        "sha512" => verify_hmac::<Sha512>(key, body, &expected),
+        _ => return Err("X-Hub-Signature uses an unsupported method"),
+    };
+
+    if !matches {
+        return Err("X-Hub-Signature does not match the notification");
+    }
+
+    Feed::from_bytes(body, charset).map(|(feed, _)| feed)
+}
+
+
+/// Whether `signature` is the HMAC of `message`, compared in constant time so that the time
+/// taken doesn't reveal how much of a forged signature was right.
+fn verify_hmac<D: Digest + BlockSizeUser>(key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    match SimpleHmac::<D>::new_from_slice(key) {
+        Ok(mac) => mac.chain_update(message).verify_slice(signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    s.as_bytes().chunks(2)
+        .map(|pair| match *pair {
+            [_, _] => u8::from_str_radix(::std::str::from_utf8(pair).ok()?, 16).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+
+/// Splits a header value on `separator`, except where it appears inside a `<...>` URI reference
+/// or a quoted string.
+fn split_unquoted(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_uri = false;
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' if !in_uri => in_quotes = !in_quotes,
+            '<' if !in_quotes => in_uri = true,
+            '>' if !in_quotes => in_uri = false,
+            c if c == separator && !in_uri && !in_quotes => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&s[start..]);
+    parts
+}
+
+
+fn form_encode(s: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+
+fn form_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut input = s.bytes();
+
+    while let Some(byte) = input.next() {
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [input.next()?, input.next()?];
+                bytes.push(u8::from_str_radix(::std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+
+#[cfg(test)]
+mod test {
+    use hmac::{Hmac, Mac};
+    use sha1::Sha1;
+    use sha2::Sha256;
+
+    use super::{from_hex, parse_notification, verify_hmac, Discovery, Mode, SubscriptionRequest};
+    use ::{Feed, Link, LinkRel};
+
+    const BODY: &[u8] = b"<feed xmlns='http://www.w3.org/2005/Atom'><id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>\
+                          <title>My Blog</title><updated>2019-04-01T07:30:00Z</updated></feed>";
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_verify_hmac() {
+        let message = b"The quick brown fox jumps over the lazy dog";
+        let signature = from_hex("de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9").unwrap();
+        assert!(verify_hmac::<Sha1>(b"key", message, &signature));
+        assert!(!verify_hmac::<Sha1>(b"kex", message, &signature));
+        assert!(!verify_hmac::<Sha1>(b"key", message, &signature[1..]));
+
+        let signature = from_hex("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8").unwrap();
+        assert!(verify_hmac::<Sha256>(b"key", message, &signature));
+
+        assert_eq!(from_hex("0g"), None);
+        assert_eq!(from_hex("abc"), None);
+    }
+
+    #[test]
+    fn test_parse_notification() {
+        let mac = Hmac::<Sha1>::new_from_slice(b"s3cr3t").unwrap().chain_update(BODY);
+        let signature = format!("sha1={}", to_hex(&mac.finalize().into_bytes()));
+        let feed = parse_notification(BODY, Some(&signature), "s3cr3t", None).unwrap();
+        assert_eq!(feed.title, "My Blog");
+
+        let mac = Hmac::<Sha256>::new_from_slice(b"s3cr3t").unwrap().chain_update(BODY);
+        let signature = format!("SHA256={}", to_hex(&mac.finalize().into_bytes()).to_uppercase());
+        assert!(parse_notification(BODY, Some(&signature), "s3cr3t", None).is_ok());
+
+        assert_eq!(parse_notification(BODY, Some(&signature), "wrong", None).err(),
+                   Some("X-Hub-Signature does not match the notification"));
+        assert_eq!(parse_notification(BODY, None, "s3cr3t", None).err(),
+                   Some("Notification is missing an X-Hub-Signature"));
+        assert_eq!(parse_notification(BODY, Some("md5=abc"), "s3cr3t", None).err(),
+                   Some("X-Hub-Signature uses an unsupported method"));
+    }
+
+    #[test]
+    fn test_discovery() {
+        let feed = Feed {
+            links: vec![
+                Link { href: "https://test.blog/".to_string(), ..Default::default() },
+                Link { href: "https://hub1.example.com/".to_string(), rel: Some(LinkRel::Hub), ..Default::default() },
+                Link { href: "https://test.blog/feed.atom".to_string(), rel: Some(LinkRel::Self_), ..Default::default() },
+                Link { href: "https://hub2.example.com/".to_string(), rel: Some(LinkRel::Hub), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(Discovery::from_feed(&feed), Discovery {
+            hubs: vec!["https://hub1.example.com/".to_string(), "https://hub2.example.com/".to_string()],
+            topic: Some("https://test.blog/feed.atom".to_string()),
+        });
+
+        let headers = vec![
+            r#"<https://hub.example.com/>; rel="hub", <https://test.blog/>; rel=alternate"#,
+            r#"<https://test.blog/feed.atom>; type="application/atom+xml"; rel="self""#,
+        ];
+
+        assert_eq!(Discovery::from_link_headers(headers), Discovery {
+            hubs: vec!["https://hub.example.com/".to_string()],
+            topic: Some("https://test.blog/feed.atom".to_string()),
+        });
+
+        let headers = vec![
+            r#"<https://hub.example.com/push?topics=a,b>; rel="hub"; title="Hub, the first", <https://hub2.example.com/>; rel=hub"#,
+        ];
+
+        assert_eq!(Discovery::from_link_headers(headers).hubs,
+                   vec!["https://hub.example.com/push?topics=a,b".to_string(), "https://hub2.example.com/".to_string()]);
+    }
+
+    #[test]
+    fn test_unsubscribe() {
+        let request = SubscriptionRequest {
+            hub: "https://hub.example.com/".to_string(),
+            topic: "https://test.blog/feed.atom?tag=a b".to_string(),
+            callback: "https://reader.example.com/push/42".to_string(),
+            mode: Mode::Unsubscribe,
+            lease_seconds: Some(86400),
+            ..Default::default()
+        };
+
+        assert_eq!(request.form_body(), "hub.mode=unsubscribe\
+            &hub.topic=https%3A%2F%2Ftest.blog%2Ffeed.atom%3Ftag%3Da+b\
+            &hub.callback=https%3A%2F%2Freader.example.com%2Fpush%2F42\
+            &hub.lease_seconds=86400");
+
+        assert_eq!(request.verify_intent("hub.mode=unsubscribe&hub.topic=https%3A%2F%2Ftest.blog%2Ffeed.atom%3Ftag%3Da+b\
+                                          &hub.challenge=xyz"),
+                   Some("xyz".to_string()));
+        assert_eq!(request.verify_intent("hub.mode=subscribe&hub.topic=https%3A%2F%2Ftest.blog%2Ffeed.atom%3Ftag%3Da+b\
+                                          &hub.challenge=xyz"),
+                   None);
+    }
+}
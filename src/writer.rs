@@ -0,0 +1,625 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use encoding_rs::Encoding;
+use xml::{self, Element, Xml};
+
+use ::{NS, XHTML_NS, XML_NS};
+
+
+const XMLNS_NS: &str = "http://www.w3.org/2000/xmlns/";
+
+
+/// The quote character used around attribute values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteStyle {
+    Single,
+    Double,
+}
+
+
+/// The character encoding a document is written in.
+///
+/// Characters the encoding cannot represent are written as numeric character references in text
+/// and attribute values. References aren't allowed in names, comments or processing
+/// instructions, so a document with such characters there is written in UTF-8 instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputEncoding {
+    Utf8,
+    /// ISO-8859-1 proper, unlike the `windows-1252` that its label means to `Encoding`
+    Iso8859_1,
+    UsAscii,
+    /// Any other encoding supported by `encoding_rs`. Encodings it cannot write, such as UTF-16,
+    /// are replaced by UTF-8.
+    Other(&'static Encoding),
+}
+
+impl OutputEncoding {
+    /// The name used in the XML declaration.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            OutputEncoding::Utf8 => "utf-8",
+            OutputEncoding::Iso8859_1 => "ISO-8859-1",
+            OutputEncoding::UsAscii => "US-ASCII",
+            OutputEncoding::Other(encoding) => encoding.output_encoding().name(),
+        }
+    }
+
+    fn can_encode(&self, c: char) -> bool {
+        match *self {
+            OutputEncoding::Utf8 => true,
+            OutputEncoding::Iso8859_1 => (c as u32) < 0x100,
+            OutputEncoding::UsAscii => c.is_ascii(),
+            OutputEncoding::Other(encoding) => {
+                let mut buf = [0; 4];
+                let (_, _, had_errors) = encoding.encode(c.encode_utf8(&mut buf));
+                !had_errors
+            }
+        }
+    }
+
+    /// Replaces every character the encoding cannot represent with a character reference.
+    fn escape(&self, s: &str) -> String {
+        if *self == OutputEncoding::Utf8 {
+            return s.to_string();
+        }
+
+        let mut result = String::with_capacity(s.len());
+
+        for c in s.chars() {
+            if self.can_encode(c) {
+                result.push(c);
+            } else {
+                result.push_str(&format!("&#x{:X};", c as u32));
+            }
+        }
+
+        result
+    }
+
+    /// Encodes a string written by a `Writer` using this encoding.
+    fn encode(&self, s: &str) -> Vec<u8> {
+        match *self {
+            OutputEncoding::Utf8 => s.as_bytes().to_vec(),
+            OutputEncoding::Iso8859_1 | OutputEncoding::UsAscii => s.chars().map(|c| c as u8).collect(),
+            OutputEncoding::Other(encoding) => encoding.encode(s).0.into_owned(),
+        }
+    }
+}
+
+
+/// Options for serializing a `Feed` with
+/// [`Feed::to_string_with`](struct.Feed.html#method.to_string_with).
+///
+/// Attributes are always written in a deterministic order: namespace declarations first, all on
+/// the root element, then attributes without a namespace, then namespaced attributes, each
+/// ordered by namespace and name.
+///
+/// # Examples
+///
+/// ```
+/// use atom_syndication::{Feed, QuoteStyle, WriteOptions};
+///
+/// let feed = Feed {
+///     title: "My Blog".into(),
+///     ..Default::default()
+/// };
+///
+/// let options = WriteOptions {
+///     indent: Some(String::from("  ")),
+///     quote_style: QuoteStyle::Double,
+///     xml_declaration: false,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(feed.to_string_with(&options), "\
+/// <feed xmlns=\"http://www.w3.org/2005/Atom\">
+///   <id></id>
+///   <title>My Blog</title>
+///   <updated></updated>
+/// </feed>");
+/// ```
+#[derive(Clone, Debug)]
+pub struct WriteOptions {
+    /// The string written once per nesting level before each element, or `None` to write the
+    /// document on a single line. Elements containing text are never reindented.
+    pub indent: Option<String>,
+    pub quote_style: QuoteStyle,
+    /// Prefixes to bind namespaces to, keyed by namespace name. The namespace of the root element,
+    /// Atom for a feed, is the default namespace unless it is given a prefix here.
+    pub namespace_prefixes: BTreeMap<String, String>,
+    /// Whether to start with an `<?xml ...?>` declaration.
+    pub xml_declaration: bool,
+    /// Writes a deterministic form for byte comparison: UTF-8, double quotes, sorted attributes,
+    /// no XML declaration, no indentation, and start/end tag pairs for empty elements. This is not
+    /// Canonical XML, as namespace declarations are all written on the root element.
+    pub canonical: bool,
+    /// The encoding named in the XML declaration, and used by
+    /// [`Feed::to_bytes_with`](struct.Feed.html#method.to_bytes_with).
+    pub encoding: OutputEncoding,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            indent: None,
+            quote_style: QuoteStyle::Single,
+            namespace_prefixes: BTreeMap::new(),
+            xml_declaration: true,
+            canonical: false,
+            encoding: OutputEncoding::Utf8,
+        }
+    }
+}
+
+
+impl WriteOptions {
+    fn encoding(&self) -> OutputEncoding {
+        if self.canonical { OutputEncoding::Utf8 } else { self.encoding }
+    }
+}
+
+
+pub fn write_bytes(root: &Element, options: &WriteOptions) -> Vec<u8> {
+    let (out, encoding) = write_encoded(root, options);
+    encoding.encode(&out)
+}
+
+
+pub fn write(root: &Element, options: &WriteOptions) -> String {
+    write_encoded(root, options).0
+}
+
+
+/// Writes `root`, returning the encoding the document was written for.
+fn write_encoded(root: &Element, options: &WriteOptions) -> (String, OutputEncoding) {
+    let mut writer = Writer {
+        options,
+        encoding: options.encoding(),
+        prefixes: BTreeMap::new(),
+        out: String::new(),
+    };
+
+    writer.bind_prefixes(root);
+
+    if !writer.can_encode_markup(root) {
+        writer.encoding = OutputEncoding::Utf8;
+    }
+
+    if options.xml_declaration && !options.canonical {
+        writer.out.push_str(&format!(r#"<?xml version="1.0" encoding="{}"?>"#, writer.encoding.name()));
+        if options.indent.is_some() {
+            writer.out.push('\n');
+        }
+    }
+
+    writer.element(root, 0, true, false);
+    (writer.out, writer.encoding)
+}
+
+
+struct Writer<'a> {
+    options: &'a WriteOptions,
+    encoding: OutputEncoding,
+    /// Namespace name to prefix, where `None` is the default namespace.
+    prefixes: BTreeMap<String, Option<String>>,
+    out: String,
+}
+
+impl<'a> Writer<'a> {
+    fn bind_prefixes(&mut self, root: &Element) {
+        let mut namespaces = BTreeSet::new();
+        let mut hints = BTreeMap::new();
+        collect_namespaces(root, &mut namespaces, &mut hints);
+
+        let mut taken: BTreeSet<String> = self.options.namespace_prefixes.values().cloned().collect();
+        taken.insert("xml".to_string());
+        taken.insert("xmlns".to_string());
+
+        for ns in namespaces {
+            let prefix = if let Some(prefix) = self.options.namespace_prefixes.get(&ns) {
+                Some(prefix.clone())
+            } else if Some(&ns) == root.ns.as_ref() {
+                None
+            } else {
+                let hint = hints.get(&ns).filter(|p| !taken.contains(*p)).cloned();
+                let prefix = hint.unwrap_or_else(|| {
+                    (0..).map(|i| format!("ns{}", i)).find(|p| !taken.contains(p)).unwrap()
+                });
+                taken.insert(prefix.clone());
+                Some(prefix)
+            };
+
+            self.prefixes.insert(ns, prefix);
+        }
+    }
+
+    /// Whether the names, comments and processing instructions in `elem` can be written in the
+    /// encoding, as they can't contain character references.
+    fn can_encode_markup(&self, elem: &Element) -> bool {
+        let can_encode = |s: &str| s.chars().all(|c| self.encoding.can_encode(c));
+
+        let prefixes = self.prefixes.values().filter_map(|prefix| prefix.as_ref());
+        let names = elem.attributes.keys().map(|(name, _)| name).chain(Some(&elem.name));
+
+        prefixes.chain(names).all(|name| can_encode(name)) && elem.children.iter().all(|child| match *child {
+            Xml::ElementNode(ref child) => self.can_encode_markup(child),
+            Xml::CommentNode(ref text) => self.options.canonical || can_encode(text),
+            Xml::PINode(ref text) => can_encode(text),
+            Xml::CharacterNode(_) | Xml::CDATANode(_) => true,
+        })
+    }
+
+    fn qualified_name(&self, name: &str, ns: Option<&String>) -> String {
+        match ns.and_then(|ns| self.prefixes.get(ns)) {
+            Some(Some(prefix)) => format!("{}:{}", prefix, name),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Writes `elem`, which is reindented unless `in_xhtml` says it is inside XHTML content.
+    fn element(&mut self, elem: &Element, depth: usize, is_root: bool, in_xhtml: bool) {
+        let in_xhtml = in_xhtml || is_xhtml(elem);
+
+        let name = self.qualified_name(&elem.name, elem.ns.as_ref());
+
+        self.out.push('<');
+        self.out.push_str(&name);
+
+        if is_root {
+            let mut declarations: Vec<(String, String)> = self.prefixes.iter()
+                .map(|(ns, prefix)| match *prefix {
+                    Some(ref prefix) => (format!("xmlns:{}", prefix), ns.clone()),
+                    None => ("xmlns".to_string(), ns.clone()),
+                })
+                .collect();
+            declarations.sort();
+
+            for (name, value) in declarations {
+                self.attribute(&name, &value);
+            }
+        }
+
+        let mut attributes: Vec<(&Option<String>, &String, &String)> = elem.attributes.iter()
+            .filter(|&((name, ns), _)| !is_declaration(name, ns))
+            .map(|((name, ns), value)| (ns, name, value))
+            .collect();
+        attributes.sort();
+
+        for (ns, name, value) in attributes {
+            let name = match ns.as_ref().map(|ns| &ns[..]) {
+                Some(XML_NS) => format!("xml:{}", name),
+                _ => self.qualified_name(name, ns.as_ref()),
+            };
+            self.attribute(&name, value);
+        }
+
+        if elem.children.is_empty() {
+            if self.options.canonical {
+                self.out.push_str("></");
+                self.out.push_str(&name);
+                self.out.push('>');
+            } else {
+                self.out.push_str("/>");
+            }
+            return;
+        }
+
+        self.out.push('>');
+
+        let indent = match self.options.indent {
+            Some(ref indent) if !self.options.canonical && !in_xhtml && has_element_content(elem) => {
+                Some(indent.clone())
+            }
+            _ => None,
+        };
+
+        for child in &elem.children {
+            match *child {
+                Xml::ElementNode(ref child) => {
+                    if let Some(ref indent) = indent {
+                        self.newline(indent, depth + 1);
+                    }
+                    self.element(child, depth + 1, false, in_xhtml);
+                }
+                Xml::CharacterNode(ref text) => {
+                    if indent.is_none() {
+                        let escaped = self.escape_text(text);
+                        self.out.push_str(&escaped);
+                    }
+                }
+                Xml::CDATANode(ref text) => {
+                    let encoding = self.encoding;
+                    if self.options.canonical || !text.chars().all(|c| encoding.can_encode(c)) {
+                        let escaped = self.escape_text(text);
+                        self.out.push_str(&escaped);
+                    } else {
+                        self.out.push_str(&format!("<![CDATA[{}]]>", text));
+                    }
+                }
+                Xml::CommentNode(ref text) => {
+                    if !self.options.canonical {
+                        self.out.push_str(&format!("<!--{}-->", text));
+                    }
+                }
+                Xml::PINode(ref text) => self.out.push_str(&format!("<?{}?>", text)),
+            }
+        }
+
+        if let Some(ref indent) = indent {
+            self.newline(indent, depth);
+        }
+
+        self.out.push_str("</");
+        self.out.push_str(&name);
+        self.out.push('>');
+    }
+
+    fn attribute(&mut self, name: &str, value: &str) {
+        let quote = if self.options.canonical || self.options.quote_style == QuoteStyle::Double {
+            '"'
+        } else {
+            '\''
+        };
+
+        let escaped = if self.options.canonical {
+            escape_canonical(value, true)
+        } else {
+            self.encoding.escape(&xml::escape(value))
+        };
+
+        self.out.push(' ');
+        self.out.push_str(name);
+        self.out.push('=');
+        self.out.push(quote);
+        self.out.push_str(&escaped);
+        self.out.push(quote);
+    }
+
+    fn escape_text(&self, text: &str) -> String {
+        if self.options.canonical {
+            escape_canonical(text, false)
+        } else {
+            self.encoding.escape(&xml::escape(text))
+        }
+    }
+
+    fn newline(&mut self, indent: &str, depth: usize) {
+        self.out.push('\n');
+        for _ in 0..depth {
+            self.out.push_str(indent);
+        }
+    }
+}
+
+
+fn collect_namespaces(elem: &Element, namespaces: &mut BTreeSet<String>, hints: &mut BTreeMap<String, String>) {
+    if let Some(ref ns) = elem.ns {
+        namespaces.insert(ns.clone());
+    }
+
+    for (name, ns) in elem.attributes.keys() {
+        match *ns {
+            Some(ref ns) if ns != XML_NS && !is_declaration(name, &Some(ns.clone())) => {
+                namespaces.insert(ns.clone());
+            }
+            _ => (),
+        }
+    }
+
+    for (ns, prefix) in &elem.prefixes {
+        if ns != XML_NS && ns != XMLNS_NS {
+            hints.entry(ns.clone()).or_insert_with(|| prefix.clone());
+        }
+    }
+
+    for child in &elem.children {
+        if let Xml::ElementNode(ref child) = *child {
+            collect_namespaces(child, namespaces, hints);
+        }
+    }
+}
+
+
+fn is_declaration(name: &str, ns: &Option<String>) -> bool {
+    match *ns {
+        Some(ref ns) => ns == XMLNS_NS,
+        None => name == "xmlns",
+    }
+}
+
+
+/// Whether `elem` is XHTML, or an Atom text construct holding XHTML, where whitespace between
+/// elements is part of the content.
+fn is_xhtml(elem: &Element) -> bool {
+    match elem.ns.as_ref().map(|ns| &ns[..]) {
+        Some(XHTML_NS) => true,
+        Some(NS) => elem.get_attribute("type", None).map(str::trim) == Some("xhtml"),
+        _ => false,
+    }
+}
+
+
+/// Elements whose only text is whitespace can be reindented without changing their meaning.
+fn has_element_content(elem: &Element) -> bool {
+    elem.children.iter().all(|child| match *child {
+        Xml::ElementNode(_) => true,
+        Xml::CharacterNode(ref text) => text.trim().is_empty(),
+        _ => false,
+    }) && elem.children.iter().any(|child| matches!(*child, Xml::ElementNode(_)))
+}
+
+
+// https://www.w3.org/TR/xml-c14n#ProcessingModel
+fn escape_canonical(input: &str, attribute: bool) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' if !attribute => result.push_str("&gt;"),
+            '"' if attribute => result.push_str("&quot;"),
+            '\t' if attribute => result.push_str("&#x9;"),
+            '\n' if attribute => result.push_str("&#xA;"),
+            '\r' => result.push_str("&#xD;"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    use encoding_rs::SHIFT_JIS;
+
+    use super::{OutputEncoding, WriteOptions};
+    use ::{Entry, Feed, Link, LinkRel, Person, Text, TextType};
+    use ::extension::ExtensionElement;
+
+    fn feed() -> Feed {
+        Feed {
+            id: "urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6".to_string(),
+            title: "Tom & Jerry".into(),
+            links: vec![
+                Link {
+                    href: "http://test.blog/".to_string(),
+                    rel: Some(LinkRel::Alternate),
+                    mediatype: Some("text/html".into()),
+                    hreflang: Some("en".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_attribute_order_is_stable() {
+        let expected = "<link href='http://test.blog/' hreflang='en' rel='alternate' type='text/html'/>";
+        for _ in 0..10 {
+            assert!(feed().to_string().contains(expected));
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        let options = WriteOptions {
+            canonical: true,
+            indent: Some("  ".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(feed().to_string_with(&options), "\
+            <feed xmlns=\"http://www.w3.org/2005/Atom\">\
+            <id>urn:uuid:b3420f84-6bdf-4f46-a225-f1b9a14703b6</id>\
+            <title>Tom &amp; Jerry</title>\
+            <updated></updated>\
+            <link href=\"http://test.blog/\" hreflang=\"en\" rel=\"alternate\" type=\"text/html\"></link>\
+            </feed>");
+    }
+
+    #[test]
+    fn test_indent_keeps_xhtml_content() {
+        let mut feed = feed();
+        feed.entries.push(Entry {
+            content: Some(Text {
+                value: "<em>A</em><strong>B</strong>".to_string(),
+                text_type: TextType::Xhtml,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let options = WriteOptions {
+            indent: Some("  ".to_string()),
+            ..Default::default()
+        };
+
+        let atom_string = feed.to_string_with(&options);
+        assert!(atom_string.contains("\n    <content type='xhtml'><xhtml:div><xhtml:em>A</xhtml:em>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.entries[0].content.as_ref().unwrap(), "<em>A</em><strong>B</strong>");
+    }
+
+    #[test]
+    fn test_output_encoding_round_trip() {
+        let mut feed = feed();
+        feed.title = "\u{65e5}\u{672c} \u{2603}".into();
+
+        let options = WriteOptions {
+            encoding: OutputEncoding::Other(SHIFT_JIS),
+            ..Default::default()
+        };
+
+        let atom_bytes = feed.to_bytes_with(&options);
+        assert!(atom_bytes.starts_with(b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?>"));
+
+        let (feed, encoding) = Feed::from_bytes(&atom_bytes, None).unwrap();
+        assert_eq!(encoding, SHIFT_JIS);
+        assert_eq!(feed.title, "\u{65e5}\u{672c} \u{2603}");
+
+        let options = WriteOptions {
+            encoding: OutputEncoding::UsAscii,
+            ..Default::default()
+        };
+        assert!(feed.to_string_with(&options).contains("<title>&#x65E5;&#x672C; &#x2603;</title>"));
+    }
+
+    #[test]
+    fn test_output_encoding_escapes_only_text() {
+        let mut feed = feed();
+        feed.title = "\u{418}\u{43c}\u{44f}".into();
+
+        let options = WriteOptions {
+            encoding: OutputEncoding::UsAscii,
+            ..Default::default()
+        };
+        let atom_string = feed.to_string_with(&options);
+        assert!(atom_string.starts_with("<?xml version=\"1.0\" encoding=\"US-ASCII\"?>"));
+        assert!(atom_string.contains("<title>&#x418;&#x43C;&#x44F;</title>"));
+
+        // A name can't hold a character reference, so the whole document falls back to UTF-8
+        feed.authors.push(Person {
+            name: "Ivan".to_string(),
+            extensions: vec![ExtensionElement {
+                name: "\u{418}\u{43c}\u{44f}".to_string(),
+                ns: Some("http://test.blog/person".to_string()),
+                value: Some("\u{418}\u{432}\u{430}\u{43d}".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+
+        let atom_bytes = feed.to_bytes_with(&options);
+        assert!(atom_bytes.starts_with(b"<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+
+        let (feed, _) = Feed::from_bytes(&atom_bytes, None).unwrap();
+        assert_eq!(feed.title, "\u{418}\u{43c}\u{44f}");
+        assert_eq!(feed.authors[0].extensions[0].name, "\u{418}\u{43c}\u{44f}");
+        assert_eq!(feed.authors[0].extensions[0].value, Some("\u{418}\u{432}\u{430}\u{43d}".to_string()));
+    }
+
+    #[test]
+    fn test_atom_prefix() {
+        let mut namespace_prefixes = BTreeMap::new();
+        namespace_prefixes.insert(::NS.to_string(), "atom".to_string());
+
+        let options = WriteOptions {
+            namespace_prefixes,
+            xml_declaration: false,
+            ..Default::default()
+        };
+
+        let atom_string = feed().to_string_with(&options);
+        assert!(atom_string.starts_with("<atom:feed xmlns:atom='http://www.w3.org/2005/Atom'><atom:id>"));
+
+        let feed = Feed::from_str(&atom_string).unwrap();
+        assert_eq!(feed.title, "Tom & Jerry");
+    }
+}